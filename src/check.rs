@@ -0,0 +1,154 @@
+use dotenv::dotenv;
+use ipnet::IpNet;
+use std::env;
+use std::net::IpAddr;
+use std::time::Duration;
+use tokio::time::timeout;
+use openssl::x509::X509;
+use crate::database::connection::{self, CERT_PATH};
+use crate::mailer::config::MailerConfig;
+use crate::mailer::queue;
+use crate::requests::middleware::context::ProxyHeader;
+use crate::requests::middleware::{cors, masking};
+use crate::requests::routes::site::security_txt;
+use crate::requests::server;
+use crate::utils::file_system::fs_read;
+
+struct Check {
+    name: &'static str,
+    result: Result<String, String>,
+}
+
+fn check_config() -> Vec<Check> {
+    let mut checks = vec![
+        Check {
+            name: "config.database_url",
+            result: env::var("database_url").map(|_| String::from("set")).map_err(|_| String::from("not set")),
+        },
+        Check {
+            name: "config.listen",
+            result: server::listen_config().map(|listeners| format!("{:?}", listeners)),
+        },
+    ];
+
+    let app_env = env::var("app_env").unwrap_or_default().to_lowercase();
+    checks.push(Check {
+        name: "config.app_env",
+        result: match app_env.as_str() {
+            "" | "development" | "dev" | "staging" | "production" | "prod" => Ok(format!("{:?}", crate::utils::environment::current())),
+            other => Err(format!("unknown environment {:?}", other)),
+        },
+    });
+
+    let invalid: Vec<String> = env::var("trusted_proxies")
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty() && item.parse::<IpNet>().is_err() && item.parse::<IpAddr>().is_err())
+        .map(String::from)
+        .collect();
+    checks.push(Check {
+        name: "config.cors",
+        result: cors::validate().map(|_| String::from("valid")),
+    });
+    checks.push(Check {
+        name: "config.data_masking",
+        result: masking::validate().map(|_| String::from("valid")),
+    });
+    if env::var("security_contact").is_ok() {
+        checks.push(Check {
+            name: "config.security_expires",
+            result: security_txt::check_expires().map(|days| format!("{} days left", days)),
+        });
+    }
+    checks.push(Check {
+        name: "config.trusted_proxy_header",
+        result: ProxyHeader::from_env().map(|header| format!("{:?}", header)),
+    });
+    checks.push(Check {
+        name: "config.trusted_proxies",
+        result: if invalid.is_empty() { Ok(String::from("valid")) } else { Err(format!("invalid entries {:?}", invalid)) },
+    });
+
+    checks
+}
+
+// The PEM has to hold certificates and load the way the connection loads it
+async fn check_cert() -> Result<String, String> {
+    if !fs_read::file_exists(CERT_PATH).await {
+        return Err(format!("{} not found", CERT_PATH));
+    }
+    let pem = tokio::fs::read(CERT_PATH)
+        .await
+        .map_err(|e| format!("{}: {}", CERT_PATH, e))?;
+    let certs = X509::stack_from_pem(&pem).map_err(|e| format!("{}: invalid PEM: {}", CERT_PATH, e))?;
+    if certs.is_empty() {
+        return Err(format!("{}: no certificates", CERT_PATH));
+    }
+    connection::tls_connector().map_err(|e| format!("{}: {}", CERT_PATH, e))?;
+    Ok(format!("{}, certificates: {}", CERT_PATH, certs.len()))
+}
+
+// Own connection instead of init_connection, which panics on failures
+async fn connect_database(database_url: &str) -> Result<String, String> {
+    let connector = connection::tls_connector().map_err(|e| e.to_string())?;
+    let (client, connection) = tokio_postgres::connect(database_url, connector)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    tokio::select! {
+        result = connection => Err(match result {
+            Ok(()) => String::from("connection closed"),
+            Err(e) => e.to_string(),
+        }),
+        row = client.query_one("SELECT 1 + 1", &[]) => match row {
+            Ok(row) if row.try_get::<_, i32>(0).is_ok_and(|value| value == 2) => Ok(String::from("connected")),
+            Ok(_) => Err(String::from("unexpected query result")),
+            Err(e) => Err(e.to_string()),
+        },
+    }
+}
+
+async fn check_database(database_url: &str) -> Result<String, String> {
+    timeout(Duration::from_secs(5), connect_database(database_url))
+        .await
+        .unwrap_or_else(|_| Err(String::from("timed out")))
+}
+
+async fn check_mailer() -> Result<String, String> {
+    match MailerConfig::from_env() {
+        Some(config) => queue::check(&config).await.map(|_| format!("{:?}", config.delivery)),
+        None => Ok(String::from("disabled")),
+    }
+}
+
+// `rest_api check` / `--check`: validate the deployment without serving,
+// exit code 1 when anything failed
+pub async fn run() -> bool {
+    dotenv().ok();
+
+    let mut checks = check_config();
+    let cert = check_cert().await;
+    let cert_ok = cert.is_ok();
+    checks.push(Check { name: "ca_cert", result: cert });
+    checks.push(Check {
+        name: "database",
+        result: match env::var("database_url") {
+            Ok(database_url) if cert_ok => check_database(&database_url).await,
+            _ => Err(String::from("skipped, needs $database_url and the CA cert")),
+        },
+    });
+    checks.push(Check { name: "mailer", result: check_mailer().await });
+
+    let mut ok = true;
+    for check in &checks {
+        match &check.result {
+            Ok(detail) => println!("ok    {:<24} {}", check.name, detail),
+            Err(detail) => {
+                ok = false;
+                println!("FAIL  {:<24} {}", check.name, detail);
+            }
+        }
+    }
+    ok
+}
@@ -3,46 +3,54 @@ use std::env;
 use tokio_postgres::{Client, Error};
 use lazy_static::lazy_static;
 use tokio::sync::OnceCell;
+use openssl::error::ErrorStack;
 use openssl::ssl::{SslConnector, SslMethod};
 use postgres_openssl::MakeTlsConnector;
+use tracing::{error, info_span, Instrument};
 use crate::utils::file_system::fs_read;
 
 lazy_static! {
     pub static ref CLIENT: OnceCell<Client> = OnceCell::const_new();
 }
 
+pub const CERT_PATH: &str = "certs/root.crt";
+
+// TLS trusting only the CA in CERT_PATH
+pub fn tls_connector() -> Result<MakeTlsConnector, ErrorStack> {
+    let mut builder = SslConnector::builder(SslMethod::tls())?;
+    builder.set_ca_file(CERT_PATH)?;
+    Ok(MakeTlsConnector::new(builder.build()))
+}
+
 pub async fn init_connection() -> Result<(), Error> {
     dotenv().ok();
 
     let database_url = env::var("database_url")
         .expect("$database_url must be set");
-    let cert_path = String::from("certs/root.crt");
-
-    let mut builder = SslConnector::builder(SslMethod::tls()).unwrap();
 
-    let check = fs_read::file_exists(&cert_path).await;
+    let check = fs_read::file_exists(CERT_PATH).await;
     if !check {
         panic!("CA cert file not found");
     }
 
-    builder.set_ca_file(&cert_path).unwrap();
-    let connector = MakeTlsConnector::new(builder.build());
+    let connector = tls_connector().unwrap();
 
     let (client, connection) = tokio_postgres::connect(&database_url, connector).await?;
 
 
     tokio::spawn(async move {
         if let Err(e) = connection.await {
-            // eprintln!("connection error: {}", e);
+            error!("connection error: {}", e);
             panic!("connection error {}", e);
         }
     });
 
     let rows = client
         .query("SELECT 1 + 1", &[])
+        .instrument(info_span!("db.query", db.system = "postgresql", db.statement = "SELECT 1 + 1"))
         .await?;
 
-    let value: i64 = rows[0].get(0);
+    let value: i32 = rows[0].get(0);
     assert_eq!(value, 2);
 
     CLIENT.set(client).expect("Failed to set client");
@@ -51,5 +59,5 @@ pub async fn init_connection() -> Result<(), Error> {
 }
 
 pub async fn get_client() -> Result<&'static Client, std::io::Error> {
-    CLIENT.get().ok_or_else(|| std::io::Error::new(std::io::ErrorKind::Other, "Client not"))
+    CLIENT.get().ok_or_else(|| std::io::Error::other("Client not"))
 }
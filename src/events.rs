@@ -0,0 +1,71 @@
+pub mod log;
+
+use lazy_static::lazy_static;
+use serde_derive::Serialize;
+use std::env;
+use std::future::Future;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+lazy_static! {
+    // $event_bus_capacity: events buffered per subscriber before it lags, default 1024
+    static ref BUS: broadcast::Sender<DomainEvent> = broadcast::channel(
+        env::var("event_bus_capacity")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(1024)
+    ).0;
+}
+
+// No write path publishes these yet
+#[allow(dead_code)]
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type")]
+pub enum DomainEvent {
+    PaintingCreated { painting_id: Uuid },
+    ImageUploaded { painting_id: Uuid, image_id: Uuid },
+    OrderPaid { order_id: Uuid },
+}
+
+impl DomainEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            DomainEvent::PaintingCreated { .. } => "PaintingCreated",
+            DomainEvent::ImageUploaded { .. } => "ImageUploaded",
+            DomainEvent::OrderPaid { .. } => "OrderPaid",
+        }
+    }
+}
+
+// Fire and forget, handlers never wait on subscribers
+#[allow(dead_code)]
+pub fn publish(event: DomainEvent) {
+    if BUS.send(event).is_err() {
+        debug!("Event published without subscribers");
+    }
+}
+
+// Runs the handler for every event; a subscriber that falls behind skips
+// the events it missed instead of blocking publishers
+pub fn subscribe<F, Fut>(name: &'static str, handler: F)
+where
+    F: Fn(DomainEvent) -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send,
+{
+    let mut receiver = BUS.subscribe();
+    tokio::spawn(async move {
+        loop {
+            match receiver.recv().await {
+                Ok(event) => handler(event).await,
+                Err(RecvError::Lagged(skipped)) => warn!("Subscriber {} skipped {} events", name, skipped),
+                Err(RecvError::Closed) => break,
+            }
+        }
+    });
+}
+
+// Every subscriber is registered here
+pub fn start_subscribers() {
+    subscribe("log", log::log_event);
+}
@@ -0,0 +1,11 @@
+use tracing::info;
+use crate::events::DomainEvent;
+
+pub async fn log_event(event: DomainEvent) {
+    info!(
+        target: "api::events",
+        event = event.name(),
+        payload = %serde_json::to_string(&event).unwrap_or_default(),
+        "Domain event"
+    );
+}
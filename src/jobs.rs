@@ -0,0 +1,12 @@
+pub mod scheduler;
+pub mod purge;
+
+use crate::jobs::scheduler::Scheduler;
+
+// Every background job is registered here
+pub fn start_jobs() {
+    Scheduler::new()
+        .every("idempotency_purge", 300, purge::idempotency_keys)
+        .every("rate_limit_purge", 60, purge::rate_limit_buckets)
+        .start();
+}
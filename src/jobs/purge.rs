@@ -0,0 +1,14 @@
+use tracing::debug;
+use crate::requests::middleware::{idempotency, rate_limit};
+
+pub async fn idempotency_keys() -> Result<(), String> {
+    let removed = idempotency::purge();
+    debug!("Purged {} expired idempotency keys", removed);
+    Ok(())
+}
+
+pub async fn rate_limit_buckets() -> Result<(), String> {
+    let removed = rate_limit::purge();
+    debug!("Purged {} full rate limit buckets", removed);
+    Ok(())
+}
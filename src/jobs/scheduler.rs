@@ -0,0 +1,138 @@
+use chrono::{DateTime, Utc};
+use futures_util::future::BoxFuture;
+use lazy_static::lazy_static;
+use std::collections::VecDeque;
+use std::env;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{error, info, info_span, Instrument};
+
+// Runs kept in memory for inspection, oldest dropped first
+const MAX_HISTORY: usize = 100;
+
+lazy_static! {
+    static ref HISTORY: Mutex<VecDeque<Run>> = Mutex::new(VecDeque::new());
+}
+
+pub type JobFn = Arc<dyn Fn() -> BoxFuture<'static, Result<(), String>> + Send + Sync>;
+
+#[derive(Debug, Clone, Copy)]
+pub enum Schedule {
+    Every(Duration),
+    // Single run after the delay, no job registers one yet
+    #[allow(dead_code)]
+    Once(Duration),
+}
+
+struct Job {
+    name: &'static str,
+    schedule: Schedule,
+    run: JobFn,
+}
+
+// Read through history(), which nothing exposes yet
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct Run {
+    pub job: &'static str,
+    pub started: DateTime<Utc>,
+    pub duration_ms: u128,
+    pub error: Option<String>,
+}
+
+pub struct Scheduler {
+    jobs: Vec<Job>,
+}
+
+impl Scheduler {
+    pub fn new() -> Scheduler {
+        Scheduler { jobs: Vec::new() }
+    }
+
+    // $job_<name>_interval_secs overrides the default interval
+    pub fn every<F, Fut>(mut self, name: &'static str, default_secs: u64, job: F) -> Scheduler
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), String>> + Send + 'static,
+    {
+        let secs = env::var(format!("job_{}_interval_secs", name))
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(default_secs);
+        self.jobs.push(Job {
+            name,
+            schedule: Schedule::Every(Duration::from_secs(secs.max(1))),
+            run: Arc::new(move || Box::pin(job())),
+        });
+        self
+    }
+
+    #[allow(dead_code)]
+    pub fn once<F, Fut>(mut self, name: &'static str, delay: Duration, job: F) -> Scheduler
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), String>> + Send + 'static,
+    {
+        self.jobs.push(Job {
+            name,
+            schedule: Schedule::Once(delay),
+            run: Arc::new(move || Box::pin(job())),
+        });
+        self
+    }
+
+    // Spawns every job not disabled with $job_<name>_enabled=false
+    pub fn start(self) {
+        for job in self.jobs {
+            if env::var(format!("job_{}_enabled", job.name)).is_ok_and(|value| value == "false") {
+                info!("Job {} disabled", job.name);
+                continue;
+            }
+
+            tokio::spawn(async move {
+                match job.schedule {
+                    Schedule::Once(delay) => {
+                        tokio::time::sleep(delay).await;
+                        execute(job.name, &job.run).await;
+                    }
+                    Schedule::Every(period) => {
+                        let mut interval = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+                        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+                        loop {
+                            interval.tick().await;
+                            execute(job.name, &job.run).await;
+                        }
+                    }
+                }
+            });
+        }
+    }
+}
+
+async fn execute(name: &'static str, job: &JobFn) {
+    let started = Utc::now();
+    let start = Instant::now();
+    let result = job().instrument(info_span!("job", job.name = name)).await;
+
+    match &result {
+        Ok(()) => info!("Job {} finished in {}ms", name, start.elapsed().as_millis()),
+        Err(e) => error!("Job {} failed: {}", name, e),
+    }
+
+    let mut history = HISTORY.lock().unwrap();
+    if history.len() >= MAX_HISTORY {
+        history.pop_front();
+    }
+    history.push_back(Run {
+        job: name,
+        started,
+        duration_ms: start.elapsed().as_millis(),
+        error: result.err(),
+    });
+}
+
+#[allow(dead_code)]
+pub fn history() -> Vec<Run> {
+    HISTORY.lock().unwrap().iter().cloned().collect()
+}
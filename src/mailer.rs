@@ -0,0 +1,59 @@
+pub mod config;
+pub mod templates;
+pub mod queue;
+
+use std::fmt;
+use lazy_static::lazy_static;
+use tokio::sync::OnceCell;
+use tracing::warn;
+use crate::mailer::config::MailerConfig;
+use crate::mailer::queue::Mailer;
+
+lazy_static! {
+    pub static ref MAILER: OnceCell<Mailer> = OnceCell::const_new();
+}
+
+#[derive(Debug)]
+pub enum MailError {
+    Template(tera::Error),
+    Address(lettre::address::AddressError),
+    Message(lettre::error::Error),
+    Smtp(lettre::transport::smtp::Error),
+    Io(std::io::Error),
+    QueueClosed,
+}
+
+impl fmt::Display for MailError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MailError::Template(e) => write!(f, "template error: {}", e),
+            MailError::Address(e) => write!(f, "invalid address: {}", e),
+            MailError::Message(e) => write!(f, "invalid message: {}", e),
+            MailError::Smtp(e) => write!(f, "smtp error: {}", e),
+            MailError::Io(e) => write!(f, "io error: {}", e),
+            MailError::QueueClosed => write!(f, "mail queue closed"),
+        }
+    }
+}
+
+impl std::error::Error for MailError {}
+
+pub fn init_mailer() -> Result<(), MailError> {
+    let config = match MailerConfig::from_env() {
+        Some(config) => config,
+        None => {
+            warn!("Mailer disabled, set $smtp_host or $mail_dev_dir to enable it");
+            return Ok(());
+        }
+    };
+
+    if MAILER.set(Mailer::start(config)?).is_err() {
+        panic!("Failed to set mailer");
+    }
+    Ok(())
+}
+
+#[allow(dead_code)]
+pub async fn get_mailer() -> Result<&'static Mailer, std::io::Error> {
+    MAILER.get().ok_or_else(|| std::io::Error::other("Mailer not initialized"))
+}
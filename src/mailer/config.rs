@@ -0,0 +1,65 @@
+use std::env;
+use std::path::PathBuf;
+use crate::utils::environment::{self, Environment};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmtpTls {
+    Starttls,
+    Tls,
+    None,
+}
+
+#[derive(Debug, Clone)]
+pub enum Delivery {
+    Smtp {
+        host: String,
+        port: Option<u16>,
+        username: Option<String>,
+        password: Option<String>,
+        tls: SmtpTls,
+    },
+    // Dev mode, every message lands as an .eml file in the directory
+    Disk(PathBuf),
+}
+
+#[derive(Debug, Clone)]
+pub struct MailerConfig {
+    pub from: String,
+    pub delivery: Delivery,
+    pub max_attempts: u32,
+}
+
+impl MailerConfig {
+    // $mail_dev_dir forces dev mode, otherwise $smtp_host enables SMTP.
+    // Development falls back to mail_out/, other environments get no mailer.
+    pub fn from_env() -> Option<MailerConfig> {
+        let delivery = if let Ok(dir) = env::var("mail_dev_dir") {
+            Delivery::Disk(PathBuf::from(dir))
+        } else if let Ok(host) = env::var("smtp_host") {
+            Delivery::Smtp {
+                host,
+                port: env::var("smtp_port").ok().and_then(|port| port.parse().ok()),
+                username: env::var("smtp_username").ok(),
+                password: env::var("smtp_password").ok(),
+                tls: match env::var("smtp_tls").unwrap_or_default().as_str() {
+                    "tls" => SmtpTls::Tls,
+                    "none" => SmtpTls::None,
+                    _ => SmtpTls::Starttls,
+                },
+            }
+        } else if environment::current() == Environment::Development {
+            Delivery::Disk(PathBuf::from("mail_out"))
+        } else {
+            return None;
+        };
+
+        Some(MailerConfig {
+            from: env::var("mail_from").unwrap_or_else(|_| String::from("noreply@localhost")),
+            delivery,
+            max_attempts: env::var("mail_max_attempts")
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(5),
+        })
+    }
+}
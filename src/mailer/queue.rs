@@ -0,0 +1,133 @@
+use lettre::message::{Mailbox, MultiPart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::transport::smtp::client::{Tls, TlsParameters};
+use lettre::{AsyncFileTransport, AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use std::time::Duration;
+use tera::Context;
+use tokio::sync::mpsc;
+use tracing::{error, info, warn};
+use crate::mailer::MailError;
+use crate::mailer::config::{Delivery, MailerConfig, SmtpTls};
+use crate::mailer::templates::{self, Language};
+
+enum Transport {
+    Smtp(AsyncSmtpTransport<Tokio1Executor>),
+    Disk(AsyncFileTransport<Tokio1Executor>),
+}
+
+impl Transport {
+    fn from_config(delivery: &Delivery) -> Result<Transport, MailError> {
+        match delivery {
+            Delivery::Smtp { host, port, username, password, tls } => {
+                let mut builder = AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host.as_str());
+                builder = match tls {
+                    SmtpTls::None => builder.tls(Tls::None),
+                    SmtpTls::Starttls => builder.tls(Tls::Required(
+                        TlsParameters::new(host.clone()).map_err(MailError::Smtp)?
+                    )),
+                    SmtpTls::Tls => builder.tls(Tls::Wrapper(
+                        TlsParameters::new(host.clone()).map_err(MailError::Smtp)?
+                    )),
+                };
+                if let Some(port) = port {
+                    builder = builder.port(*port);
+                }
+                if let (Some(username), Some(password)) = (username, password) {
+                    builder = builder.credentials(Credentials::new(username.clone(), password.clone()));
+                }
+                Ok(Transport::Smtp(builder.build()))
+            }
+            Delivery::Disk(dir) => {
+                std::fs::create_dir_all(dir).map_err(MailError::Io)?;
+                Ok(Transport::Disk(AsyncFileTransport::new(dir)))
+            }
+        }
+    }
+
+    // Connects and authenticates for SMTP, creates the directory for disk delivery
+    async fn test(&self) -> Result<(), String> {
+        match self {
+            Transport::Smtp(smtp) => match smtp.test_connection().await {
+                Ok(true) => Ok(()),
+                Ok(false) => Err(String::from("server did not answer")),
+                Err(e) => Err(e.to_string()),
+            },
+            Transport::Disk(_) => Ok(()),
+        }
+    }
+
+    async fn deliver(&self, message: &Message) -> Result<(), String> {
+        match self {
+            Transport::Smtp(smtp) => smtp.send(message.clone()).await.map(|_| ()).map_err(|e| e.to_string()),
+            Transport::Disk(disk) => disk.send(message.clone()).await.map(|_| ()).map_err(|e| e.to_string()),
+        }
+    }
+}
+
+// Messages are rendered when queued and delivered by a background task
+pub struct Mailer {
+    from: Mailbox,
+    sender: mpsc::UnboundedSender<Message>,
+}
+
+impl Mailer {
+    pub fn start(config: MailerConfig) -> Result<Mailer, MailError> {
+        let from = config.from.parse::<Mailbox>().map_err(MailError::Address)?;
+        let transport = Transport::from_config(&config.delivery)?;
+        let (sender, receiver) = mpsc::unbounded_channel();
+
+        tokio::spawn(worker(transport, config.max_attempts.max(1), receiver));
+
+        Ok(Mailer { from, sender })
+    }
+
+    pub fn send(&self, to: &str, template: &str, language: Language, context: &Context) -> Result<(), MailError> {
+        let rendered = templates::render(template, language, context).map_err(MailError::Template)?;
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(to.parse::<Mailbox>().map_err(MailError::Address)?)
+            .subject(rendered.subject)
+            .multipart(MultiPart::alternative_plain_html(rendered.text, rendered.html))
+            .map_err(MailError::Message)?;
+
+        self.sender.send(message).map_err(|_| MailError::QueueClosed)
+    }
+}
+
+// Startup self-check: sender address and transport without sending anything
+pub async fn check(config: &MailerConfig) -> Result<(), String> {
+    config.from.parse::<Mailbox>().map_err(|e| format!("$mail_from: {}", e))?;
+    Transport::from_config(&config.delivery)
+        .map_err(|e| e.to_string())?
+        .test()
+        .await
+}
+
+// Exponential backoff, 2s, 4s, 8s... capped at 5 minutes
+fn backoff(attempt: u32) -> Duration {
+    Duration::from_secs(2u64.saturating_pow(attempt).min(300))
+}
+
+async fn worker(transport: Transport, max_attempts: u32, mut receiver: mpsc::UnboundedReceiver<Message>) {
+    let transport = std::sync::Arc::new(transport);
+
+    while let Some(message) = receiver.recv().await {
+        let transport = transport.clone();
+        // Retries run on their own so one failing message doesn't stall the queue
+        tokio::spawn(async move {
+            for attempt in 1..=max_attempts {
+                match transport.deliver(&message).await {
+                    Ok(()) => {
+                        info!("Email delivered on attempt {}", attempt);
+                        return;
+                    }
+                    Err(e) if attempt < max_attempts => {
+                        warn!("Email delivery attempt {} failed: {}", attempt, e);
+                        tokio::time::sleep(backoff(attempt)).await;
+                    }
+                    Err(e) => error!("Email dropped after {} attempts: {}", attempt, e),
+                }
+            }
+        });
+    }
+}
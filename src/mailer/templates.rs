@@ -0,0 +1,68 @@
+use lazy_static::lazy_static;
+use tera::{Context, Tera};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    Cs,
+    En,
+}
+
+impl Language {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Language::Cs => "cs",
+            Language::En => "en",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Language> {
+        match code.trim().to_lowercase().get(..2) {
+            Some("cs") => Some(Language::Cs),
+            Some("en") => Some(Language::En),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Rendered {
+    pub subject: String,
+    pub html: String,
+    pub text: String,
+}
+
+macro_rules! template {
+    ($name:literal) => {
+        ($name, include_str!(concat!("../../templates/email/", $name, ".tera")))
+    };
+}
+
+// Embedded so the binary stays the only deploy artifact. Templates are
+// <name>.<lang>.{subject,html,txt}, .html ones are autoescaped.
+lazy_static! {
+    static ref TEMPLATES: Tera = {
+        let mut tera = Tera::default();
+        tera.add_raw_templates(vec![
+            template!("layout.html"),
+            template!("message.cs.subject"),
+            template!("message.cs.html"),
+            template!("message.cs.txt"),
+            template!("message.en.subject"),
+            template!("message.en.html"),
+            template!("message.en.txt"),
+        ]).expect("Invalid email template");
+        tera
+    };
+}
+
+pub fn render(name: &str, language: Language, context: &Context) -> Result<Rendered, tera::Error> {
+    let mut context = context.clone();
+    context.insert("lang", language.as_str());
+    let base = format!("{}.{}", name, language.as_str());
+
+    Ok(Rendered {
+        subject: TEMPLATES.render(&format!("{}.subject", base), &context)?.trim().to_string(),
+        html: TEMPLATES.render(&format!("{}.html", base), &context)?,
+        text: TEMPLATES.render(&format!("{}.txt", base), &context)?,
+    })
+}
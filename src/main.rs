@@ -1,9 +1,31 @@
 mod requests;
 mod utils;
 mod database;
+mod mailer;
+mod jobs;
+mod events;
+mod notifications;
+mod check;
+
+use std::env;
+use tracing::{error, info_span, Instrument};
 
 #[tokio::main]
 async fn main() {
+    // Self-check for deploy pipelines, runs instead of the server
+    if env::args().skip(1).any(|arg| arg == "check" || arg == "--check") {
+        let ok = check::run().await;
+        std::process::exit(if ok { 0 } else { 1 });
+    }
+
+    // Logger init
+    utils::logger::init_logger();
+    let _error_reporting = utils::error_reporting::init_error_reporting();
+
+    // Mailer and notification channels init
+    mailer::init_mailer().unwrap();
+    notifications::init_notifications().unwrap();
+
     // Database init
     database::connection::init_connection()
         .await
@@ -15,12 +37,19 @@ async fn main() {
     // Test static CLIENT inicialization
     let rows = client
         .query("SELECT 1 + 1", &[])
+        .instrument(info_span!("db.query", db.system = "postgresql", db.statement = "SELECT 1 + 1"))
         .await
         .unwrap();
-    let value: i64 = rows[0].get(0);
+    let value: i32 = rows[0].get(0);
     assert_eq!(value, 2);
 
-    // Routes init
-    let routes = requests::router::router();
-    warp::serve(routes).run(([127, 0, 0, 1], 3030)).await;
+    // Background jobs, domain event subscribers
+    jobs::start_jobs();
+    events::start_subscribers();
+
+    // Server init
+    if let Err(e) = requests::server::serve(requests::server::listeners()).await {
+        error!("Server error: {}", e);
+        std::process::exit(1);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,114 @@
+pub mod email;
+pub mod telegram;
+pub mod webhook;
+
+use std::fmt;
+use async_trait::async_trait;
+use futures_util::future::join_all;
+use lazy_static::lazy_static;
+use serde_derive::Serialize;
+use tokio::sync::OnceCell;
+use tracing::{info, warn};
+use crate::mailer::MailError;
+use crate::notifications::email::EmailChannel;
+use crate::notifications::telegram::TelegramChannel;
+use crate::notifications::webhook::WebhookChannel;
+use crate::utils::environment;
+
+lazy_static! {
+    static ref CHANNELS: OnceCell<Vec<Box<dyn Channel>>> = OnceCell::const_new();
+}
+
+#[derive(Debug)]
+pub enum NotifyError {
+    Config(String),
+    Mail(MailError),
+    Http(reqwest::Error),
+    Status(u16),
+}
+
+impl fmt::Display for NotifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NotifyError::Config(e) => write!(f, "config error: {}", e),
+            NotifyError::Mail(e) => write!(f, "mail error: {}", e),
+            NotifyError::Http(e) => write!(f, "http error: {}", e),
+            NotifyError::Status(status) => write!(f, "unexpected status {}", status),
+        }
+    }
+}
+
+impl std::error::Error for NotifyError {}
+
+// Same shape as the email message template: a title, paragraphs, optional link
+#[derive(Debug, Clone, Serialize)]
+pub struct Notification {
+    pub kind: &'static str,
+    pub title: String,
+    pub paragraphs: Vec<String>,
+    pub action_url: Option<String>,
+}
+
+impl Notification {
+    // Plain text for chat channels
+    pub fn text(&self) -> String {
+        let mut lines = vec![self.title.clone()];
+        lines.extend(self.paragraphs.iter().cloned());
+        if let Some(url) = &self.action_url {
+            lines.push(url.clone());
+        }
+        lines.join("\n\n")
+    }
+}
+
+#[async_trait]
+pub trait Channel: Send + Sync {
+    fn name(&self) -> &'static str;
+    async fn send(&self, notification: &Notification) -> Result<(), NotifyError>;
+}
+
+fn channel(name: &str) -> Result<Box<dyn Channel>, NotifyError> {
+    match name {
+        "email" => Ok(Box::new(EmailChannel::from_env()?)),
+        "telegram" => Ok(Box::new(TelegramChannel::from_env()?)),
+        "webhook" => Ok(Box::new(WebhookChannel::from_env()?)),
+        other => Err(NotifyError::Config(format!("unknown channel {:?} in $notify_channels", other))),
+    }
+}
+
+// $notify_channels: comma separated email, telegram, webhook; none unless set
+pub fn init_notifications() -> Result<(), NotifyError> {
+    let names = environment::list("notify_channels").unwrap_or_default();
+    if names.is_empty() {
+        warn!("Notifications disabled, set $notify_channels to enable them");
+        return Ok(());
+    }
+
+    let channels = names
+        .iter()
+        .map(|name| channel(name))
+        .collect::<Result<Vec<_>, _>>()?;
+    info!("Notification channels: {}", names.join(", "));
+
+    if CHANNELS.set(channels).is_err() {
+        panic!("Failed to set notification channels");
+    }
+    Ok(())
+}
+
+// Every configured channel gets the notification, a failing one doesn't
+// stop the others. Unused until inquiries or orders exist to notify about.
+#[allow(dead_code)]
+pub async fn notify(notification: Notification) {
+    let channels = match CHANNELS.get() {
+        Some(channels) => channels,
+        None => return,
+    };
+
+    let results = join_all(channels.iter().map(|channel| channel.send(&notification))).await;
+    for (channel, result) in channels.iter().zip(results) {
+        if let Err(e) = result {
+            warn!("Notification {} via {} failed: {}", notification.kind, channel.name(), e);
+        }
+    }
+}
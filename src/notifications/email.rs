@@ -0,0 +1,45 @@
+use std::env;
+use async_trait::async_trait;
+use tera::Context;
+use crate::mailer;
+use crate::mailer::templates::Language;
+use crate::notifications::{Channel, Notification, NotifyError};
+
+// Goes through the mail queue, so delivery retries are the mailer's
+pub struct EmailChannel {
+    to: String,
+    language: Language,
+}
+
+impl EmailChannel {
+    // $notify_email_to, $notify_email_language (cs or en, default en)
+    pub fn from_env() -> Result<EmailChannel, NotifyError> {
+        let to = env::var("notify_email_to")
+            .map_err(|_| NotifyError::Config(String::from("$notify_email_to is not set")))?;
+        let language = env::var("notify_email_language")
+            .ok()
+            .and_then(|code| Language::from_code(&code))
+            .unwrap_or(Language::En);
+
+        Ok(EmailChannel { to, language })
+    }
+}
+
+#[async_trait]
+impl Channel for EmailChannel {
+    fn name(&self) -> &'static str {
+        "email"
+    }
+
+    async fn send(&self, notification: &Notification) -> Result<(), NotifyError> {
+        let mailer = mailer::MAILER
+            .get()
+            .ok_or_else(|| NotifyError::Config(String::from("mailer disabled")))?;
+
+        let mut context = Context::new();
+        context.insert("title", &notification.title);
+        context.insert("paragraphs", &notification.paragraphs);
+        context.insert("action_url", &notification.action_url);
+        mailer.send(&self.to, "message", self.language, &context).map_err(NotifyError::Mail)
+    }
+}
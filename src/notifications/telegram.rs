@@ -0,0 +1,51 @@
+use std::env;
+use std::time::Duration;
+use async_trait::async_trait;
+use crate::notifications::{Channel, Notification, NotifyError};
+
+pub struct TelegramChannel {
+    token: String,
+    chat_id: String,
+    client: reqwest::Client,
+}
+
+impl TelegramChannel {
+    // $telegram_bot_token, $telegram_chat_id
+    pub fn from_env() -> Result<TelegramChannel, NotifyError> {
+        let token = env::var("telegram_bot_token")
+            .map_err(|_| NotifyError::Config(String::from("$telegram_bot_token is not set")))?;
+        let chat_id = env::var("telegram_chat_id")
+            .map_err(|_| NotifyError::Config(String::from("$telegram_chat_id is not set")))?;
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .map_err(NotifyError::Http)?;
+
+        Ok(TelegramChannel { token, chat_id, client })
+    }
+}
+
+#[async_trait]
+impl Channel for TelegramChannel {
+    fn name(&self) -> &'static str {
+        "telegram"
+    }
+
+    async fn send(&self, notification: &Notification) -> Result<(), NotifyError> {
+        let response = self.client
+            .post(format!("https://api.telegram.org/bot{}/sendMessage", self.token))
+            .json(&serde_json::json!({
+                "chat_id": self.chat_id,
+                "text": notification.text(),
+            }))
+            .send()
+            .await
+            // The error URL would carry the bot token into the logs
+            .map_err(|e| NotifyError::Http(e.without_url()))?;
+
+        if !response.status().is_success() {
+            return Err(NotifyError::Status(response.status().as_u16()));
+        }
+        Ok(())
+    }
+}
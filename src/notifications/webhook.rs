@@ -0,0 +1,49 @@
+use std::env;
+use std::time::Duration;
+use async_trait::async_trait;
+use crate::notifications::{Channel, Notification, NotifyError};
+
+// POSTs the notification as JSON, any 2xx counts as delivered
+pub struct WebhookChannel {
+    url: String,
+    secret: Option<String>,
+    client: reqwest::Client,
+}
+
+impl WebhookChannel {
+    // $notify_webhook_url, $notify_webhook_secret sent as a bearer token
+    pub fn from_env() -> Result<WebhookChannel, NotifyError> {
+        let url = env::var("notify_webhook_url")
+            .map_err(|_| NotifyError::Config(String::from("$notify_webhook_url is not set")))?;
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .map_err(NotifyError::Http)?;
+
+        Ok(WebhookChannel {
+            url,
+            secret: env::var("notify_webhook_secret").ok(),
+            client,
+        })
+    }
+}
+
+#[async_trait]
+impl Channel for WebhookChannel {
+    fn name(&self) -> &'static str {
+        "webhook"
+    }
+
+    async fn send(&self, notification: &Notification) -> Result<(), NotifyError> {
+        let mut request = self.client.post(&self.url).json(notification);
+        if let Some(secret) = &self.secret {
+            request = request.bearer_auth(secret);
+        }
+        let response = request.send().await.map_err(NotifyError::Http)?;
+
+        if !response.status().is_success() {
+            return Err(NotifyError::Status(response.status().as_u16()));
+        }
+        Ok(())
+    }
+}
@@ -1,3 +1,6 @@
 pub mod router;
 pub mod routes;
-pub mod dto;
\ No newline at end of file
+pub mod dto;
+pub mod middleware;
+pub mod openapi;
+pub mod server;
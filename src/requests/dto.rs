@@ -1,2 +1,7 @@
 pub mod employee;
-pub mod salute_you;
\ No newline at end of file
+pub mod salute_you;
+pub mod health;
+pub mod batch;
+pub mod painting_stats;
+pub mod qr;
+pub mod limits;
\ No newline at end of file
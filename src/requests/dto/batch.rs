@@ -0,0 +1,19 @@
+use serde_derive::{Deserialize, Serialize};
+use std::collections::HashMap;
+use utoipa::ToSchema;
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct BatchRequest {
+    pub method: String,
+    pub path: String,
+    pub headers: Option<HashMap<String, String>>,
+    #[schema(value_type = Option<Object>)]
+    pub body: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+pub struct BatchResponse {
+    pub status: u16,
+    #[schema(value_type = Object)]
+    pub body: serde_json::Value,
+}
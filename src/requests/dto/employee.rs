@@ -1,6 +1,7 @@
 use serde_derive::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, ToSchema)]
 pub struct Employee {
     pub rate: u32,
     pub name: String,
@@ -0,0 +1,32 @@
+use serde_derive::Serialize;
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct HealthCheck {
+    pub name: String,
+    pub ok: bool,
+    pub latency_ms: u128,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct HealthReport {
+    pub status: String,
+    pub version: String,
+    pub checks: Vec<HealthCheck>,
+}
+
+impl HealthReport {
+    pub fn new(checks: Vec<HealthCheck>) -> HealthReport {
+        let status = if checks.iter().all(|check| check.ok) { "ok" } else { "error" };
+        HealthReport {
+            status: String::from(status),
+            version: String::from(env!("CARGO_PKG_VERSION")),
+            checks,
+        }
+    }
+
+    pub fn is_ok(&self) -> bool {
+        self.status == "ok"
+    }
+}
@@ -0,0 +1,19 @@
+use serde_derive::Serialize;
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Quota {
+    pub bucket: String,
+    pub limit: u32,
+    pub period_secs: u64,
+    pub remaining: u32,
+    // Seconds until the bucket is full again
+    pub reset: u64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Limits {
+    // Always "ip" until API keys are validated
+    pub client: String,
+    pub quotas: Vec<Quota>,
+}
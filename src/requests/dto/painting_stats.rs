@@ -0,0 +1,24 @@
+use serde_derive::Serialize;
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Range {
+    pub min: Option<i64>,
+    pub max: Option<i64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PriceStats {
+    pub min: Option<i64>,
+    pub max: Option<i64>,
+    pub average: Option<f64>,
+}
+
+// Bounds for the catalogue filter sliders, nulls when nothing is priced/measured
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PaintingStats {
+    pub count: i64,
+    pub price: PriceStats,
+    pub width: Range,
+    pub height: Range,
+}
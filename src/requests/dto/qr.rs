@@ -0,0 +1,20 @@
+use serde_derive::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+// QR error correction: L 7%, M 15%, Q 25%, H 30% of the code recoverable
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize, ToSchema)]
+pub enum Ecc {
+    L,
+    M,
+    Q,
+    H,
+}
+
+#[derive(Debug, Deserialize, Serialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct QrParams {
+    // Approximate image width in pixels, 64 to 2048, default 300
+    pub size: Option<u32>,
+    // Error correction level, default M
+    pub ecc: Option<Ecc>,
+}
@@ -1,6 +1,8 @@
 use serde_derive::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct SaluteYou {
     pub first_name: String,
     pub last_name: String,
@@ -0,0 +1,13 @@
+pub mod request_id;
+pub mod context;
+pub mod error_report;
+pub mod access_log;
+pub mod cors;
+pub mod compression;
+pub mod rate_limit;
+pub mod idempotency;
+pub mod maintenance;
+pub mod security_headers;
+pub mod openapi_validation;
+pub mod json_case;
+pub mod masking;
@@ -0,0 +1,38 @@
+use warp::hyper::body::HttpBody;
+use warp::reply::Response;
+use uuid::Uuid;
+use tracing::info;
+use crate::requests::middleware::context::RequestContext;
+
+// Collapse IDs in the path so lines group by route, e.g. /promote/{id}
+pub fn path_template(path: &str) -> String {
+    path
+        .split('/')
+        .map(|segment| {
+            if !segment.is_empty()
+                && (segment.parse::<i64>().is_ok() || Uuid::parse_str(segment).is_ok()) {
+                "{id}"
+            } else {
+                segment
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+pub fn log(context: &RequestContext, request_id: &str, response: &Response) {
+    let bytes = response.body().size_hint().exact();
+    let client_ip = context.client_ip.map(|ip| ip.to_string());
+
+    info!(
+        target: "api::access",
+        method = %context.method,
+        route = %path_template(&context.path),
+        status = response.status().as_u16(),
+        latency_ms = context.started.elapsed().as_secs_f64() * 1000.0,
+        bytes = bytes,
+        client_ip = client_ip.as_deref(),
+        request_id = request_id,
+        "request completed"
+    );
+}
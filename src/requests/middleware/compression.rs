@@ -0,0 +1,186 @@
+use warp::http::header::{HeaderValue, CONTENT_ENCODING, CONTENT_LENGTH, CONTENT_TYPE, VARY};
+use warp::hyper::body::{self, Body, HttpBody};
+use warp::reply::Response;
+use flate2::write::GzEncoder;
+use lazy_static::lazy_static;
+use std::env;
+use std::io::Write;
+use tracing::error;
+use crate::requests::middleware::context::RequestContext;
+
+lazy_static! {
+    // $compression_min_size: smaller bodies are sent as is, default 1 KiB
+    static ref MIN_SIZE: u64 = env::var("compression_min_size")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(1024);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Brotli,
+    Gzip,
+}
+
+impl Encoding {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Encoding::Brotli => "br",
+            Encoding::Gzip => "gzip",
+        }
+    }
+}
+
+// Highest q-value wins, brotli on a tie. Codings are case-insensitive, a
+// coding not listed takes the q of "*", q=0 refuses it.
+pub fn negotiate(accept_encoding: &str) -> Option<Encoding> {
+    let accepted: Vec<(String, f32)> = accept_encoding
+        .split(',')
+        .filter_map(|item| {
+            let mut parts = item.trim().split(';');
+            let coding = parts.next()?.trim().to_ascii_lowercase();
+            if coding.is_empty() {
+                return None;
+            }
+            let q = parts
+                .find_map(|param| {
+                    let (key, value) = param.split_once('=')?;
+                    key.trim().eq_ignore_ascii_case("q").then(|| value.trim().parse::<f32>().ok()).flatten()
+                })
+                .unwrap_or(1.0);
+            Some((coding, q))
+        })
+        .collect();
+
+    let q = |names: &[&str]| {
+        accepted
+            .iter()
+            .find(|(coding, _)| names.contains(&coding.as_str()))
+            .or_else(|| accepted.iter().find(|(coding, _)| coding == "*"))
+            .map_or(0.0, |(_, q)| *q)
+    };
+    let (brotli, gzip) = (q(&["br"]), q(&["gzip", "x-gzip"]));
+
+    if brotli > 0.0 && brotli >= gzip {
+        Some(Encoding::Brotli)
+    } else if gzip > 0.0 {
+        Some(Encoding::Gzip)
+    } else {
+        None
+    }
+}
+
+fn is_compressible(response: &Response) -> bool {
+    let content_type = response.headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+
+    content_type.starts_with("text/")
+        || content_type.starts_with("application/json")
+        || content_type.starts_with("application/javascript")
+        || content_type.starts_with("application/xml")
+        || content_type.starts_with("image/svg+xml")
+}
+
+fn encode(encoding: Encoding, data: &[u8]) -> std::io::Result<Vec<u8>> {
+    match encoding {
+        Encoding::Brotli => {
+            let mut output = Vec::new();
+            {
+                let mut writer = brotli::CompressorWriter::new(&mut output, 4096, 5, 22);
+                writer.write_all(data)?;
+            }
+            Ok(output)
+        }
+        Encoding::Gzip => {
+            let mut writer = GzEncoder::new(Vec::new(), flate2::Compression::default());
+            writer.write_all(data)?;
+            writer.finish()
+        }
+    }
+}
+
+// Compress JSON/HTML/text bodies above the size threshold
+pub async fn compress(context: &RequestContext, response: Response) -> Response {
+    if !is_compressible(&response) || response.headers().contains_key(CONTENT_ENCODING) {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    parts.headers.append(VARY, HeaderValue::from_static("accept-encoding"));
+
+    let encoding = match context.accept_encoding.as_deref().and_then(negotiate) {
+        Some(encoding) => encoding,
+        None => return Response::from_parts(parts, body),
+    };
+    // Only full in-memory bodies, streams keep flowing uncompressed
+    match body.size_hint().exact() {
+        Some(size) if size >= *MIN_SIZE => (),
+        _ => return Response::from_parts(parts, body),
+    }
+
+    let bytes = match body::to_bytes(body).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            error!("Failed to buffer response body: {}", e);
+            return Response::from_parts(parts, Body::empty());
+        }
+    };
+
+    match encode(encoding, &bytes) {
+        Ok(compressed) => {
+            parts.headers.insert(CONTENT_ENCODING, HeaderValue::from_static(encoding.as_str()));
+            parts.headers.remove(CONTENT_LENGTH);
+            Response::from_parts(parts, Body::from(compressed))
+        }
+        Err(e) => {
+            error!("Failed to compress response: {}", e);
+            Response::from_parts(parts, Body::from(bytes))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn q_zero_refuses_a_coding() {
+        assert_eq!(negotiate("br;q=0, gzip"), Some(Encoding::Gzip));
+        assert_eq!(negotiate("gzip;Q=0, br;q=0"), None);
+        assert_eq!(negotiate("*;q=0"), None);
+    }
+
+    #[test]
+    fn highest_q_wins() {
+        assert_eq!(negotiate("gzip;q=1, br;q=0.1"), Some(Encoding::Gzip));
+        assert_eq!(negotiate("br;q=0.5, *;q=0.8"), Some(Encoding::Gzip));
+    }
+
+    #[test]
+    fn codings_are_case_insensitive() {
+        assert_eq!(negotiate("GZIP"), Some(Encoding::Gzip));
+        assert_eq!(negotiate("BR, gzip"), Some(Encoding::Brotli));
+        assert_eq!(negotiate("x-gzip"), Some(Encoding::Gzip));
+    }
+
+    #[test]
+    fn wildcard_covers_unlisted_codings() {
+        assert_eq!(negotiate("*"), Some(Encoding::Brotli));
+        assert_eq!(negotiate("br;q=0, *"), Some(Encoding::Gzip));
+    }
+
+    #[test]
+    fn brotli_wins_a_tie() {
+        assert_eq!(negotiate("gzip, br"), Some(Encoding::Brotli));
+        assert_eq!(negotiate("gzip;q=0.5, br;q=0.5"), Some(Encoding::Brotli));
+    }
+
+    #[test]
+    fn nothing_supported() {
+        assert_eq!(negotiate(""), None);
+        assert_eq!(negotiate("identity"), None);
+        assert_eq!(negotiate("deflate, compress"), None);
+    }
+}
@@ -0,0 +1,234 @@
+use warp::Filter;
+use warp::http::{HeaderMap, Method};
+use warp::path::FullPath;
+use std::convert::Infallible;
+use std::env;
+use std::net::{IpAddr, SocketAddr};
+use std::time::Instant;
+use ipnet::IpNet;
+use lazy_static::lazy_static;
+
+lazy_static! {
+    // $trusted_proxies: comma separated IPs or CIDRs (10.0.0.0/8) allowed to
+    // set the proxy header
+    static ref TRUSTED_PROXIES: Vec<IpNet> = env::var("trusted_proxies")
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter_map(|item| item.parse::<IpNet>().ok().or_else(|| item.parse::<IpAddr>().ok().map(IpNet::from)))
+        .collect();
+    static ref PROXY_HEADER: ProxyHeader = ProxyHeader::from_env().expect("Invalid $trusted_proxy_header");
+}
+
+// The one header the trusted proxies append to. Any other proxy header is
+// client controlled and never read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProxyHeader {
+    XForwardedFor,
+    Forwarded,
+}
+
+impl ProxyHeader {
+    // $trusted_proxy_header: x-forwarded-for (nginx, the default) or forwarded
+    pub fn from_env() -> Result<ProxyHeader, String> {
+        match env::var("trusted_proxy_header").unwrap_or_default().to_lowercase().as_str() {
+            "" | "x-forwarded-for" => Ok(ProxyHeader::XForwardedFor),
+            "forwarded" => Ok(ProxyHeader::Forwarded),
+            other => Err(format!("unknown header {:?}, expected x-forwarded-for or forwarded", other)),
+        }
+    }
+}
+
+// Peer address set by the server, warp only knows it under warp::serve
+#[derive(Debug, Clone, Copy)]
+pub struct PeerAddr(pub SocketAddr);
+
+#[derive(Debug, Clone)]
+pub struct RequestContext {
+    pub method: Method,
+    pub path: String,
+    pub query: String,
+    pub client_ip: Option<IpAddr>,
+    pub accept_encoding: Option<String>,
+    pub started: Instant,
+}
+
+// Keep only the query keys, values may carry personal data
+fn sanitize_query(query: &str) -> String {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| format!("{}=[filtered]", pair.split('=').next().unwrap_or_default()))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+// RFC 7239 for= node: 192.0.2.1, "192.0.2.1:80", "[2001:db8::1]:80".
+// Obfuscated and "unknown" nodes don't parse and are skipped.
+fn parse_node(node: &str) -> Option<IpAddr> {
+    let node = node.trim().trim_matches('"');
+    if let Some(rest) = node.strip_prefix('[') {
+        return rest.split(']').next()?.parse().ok();
+    }
+    node.parse().ok().or_else(|| node.parse::<SocketAddr>().ok().map(|addr| addr.ip()))
+}
+
+// Hops in order from the configured header only
+fn forwarded_for(headers: &HeaderMap, header: ProxyHeader) -> Vec<IpAddr> {
+    let values = |name| headers.get_all(name).iter().filter_map(|value| value.to_str().ok());
+
+    match header {
+        ProxyHeader::Forwarded => values("forwarded")
+            .flat_map(|value| value.split(','))
+            .filter_map(|element| {
+                element.split(';').find_map(|pair| {
+                    let (key, value) = pair.split_once('=')?;
+                    key.trim().eq_ignore_ascii_case("for").then(|| parse_node(value)).flatten()
+                })
+            })
+            .collect(),
+        ProxyHeader::XForwardedFor => values("x-forwarded-for")
+            .flat_map(|value| value.split(','))
+            .filter_map(parse_node)
+            .collect(),
+    }
+}
+
+// Walk the forwarded hops from the right, skipping our own proxies. The
+// first untrusted hop is the client, anything left of it may be forged.
+fn resolve(remote: Option<SocketAddr>, headers: &HeaderMap, trusted: &[IpNet], header: ProxyHeader) -> Option<IpAddr> {
+    let is_trusted = |ip: &IpAddr| trusted.iter().any(|net| net.contains(ip));
+    let peer = remote?.ip();
+    if !is_trusted(&peer) {
+        return Some(peer);
+    }
+
+    Some(
+        forwarded_for(headers, header)
+            .into_iter()
+            .rev()
+            .find(|ip| !is_trusted(ip))
+            .unwrap_or(peer)
+    )
+}
+
+pub fn client_ip(remote: Option<SocketAddr>, headers: &HeaderMap) -> Option<IpAddr> {
+    resolve(remote, headers, &TRUSTED_PROXIES, *PROXY_HEADER)
+}
+
+pub fn remote() -> impl Filter<Extract = (Option<SocketAddr>,), Error = Infallible> + Clone {
+    warp::addr::remote()
+        .and(warp::ext::optional::<PeerAddr>())
+        .map(|remote: Option<SocketAddr>, peer: Option<PeerAddr>| remote.or(peer.map(|peer| peer.0)))
+}
+
+pub fn context() -> impl Filter<Extract = (RequestContext,), Error = Infallible> + Clone {
+    warp::method()
+        .and(warp::path::full())
+        .and(warp::query::raw().or(warp::any().map(String::new)).unify())
+        .and(remote())
+        .and(warp::header::headers_cloned())
+        .map(|method: Method, path: FullPath, query: String, remote: Option<SocketAddr>, headers: HeaderMap| {
+            RequestContext {
+                method,
+                path: path.as_str().to_string(),
+                query: sanitize_query(&query),
+                client_ip: client_ip(remote, &headers),
+                accept_encoding: headers
+                    .get("accept-encoding")
+                    .and_then(|value| value.to_str().ok())
+                    .map(String::from),
+                started: Instant::now(),
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use warp::http::HeaderValue;
+
+    fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.append(*name, HeaderValue::from_static(value));
+        }
+        headers
+    }
+
+    fn proxies() -> Vec<IpNet> {
+        vec!["10.0.0.0/8".parse().unwrap()]
+    }
+
+    fn peer(ip: &str) -> Option<SocketAddr> {
+        Some(SocketAddr::new(ip.parse().unwrap(), 40000))
+    }
+
+    fn ip(value: &str) -> Option<IpAddr> {
+        Some(value.parse().unwrap())
+    }
+
+    #[test]
+    fn parse_node_forms() {
+        assert_eq!(parse_node("192.0.2.1"), ip("192.0.2.1"));
+        assert_eq!(parse_node(" 192.0.2.1 "), ip("192.0.2.1"));
+        assert_eq!(parse_node("\"192.0.2.1:8080\""), ip("192.0.2.1"));
+        assert_eq!(parse_node("2001:db8::1"), ip("2001:db8::1"));
+        assert_eq!(parse_node("\"[2001:db8::1]:443\""), ip("2001:db8::1"));
+        assert_eq!(parse_node("[2001:db8::1]"), ip("2001:db8::1"));
+        assert_eq!(parse_node("unknown"), None);
+        assert_eq!(parse_node("_hidden"), None);
+        assert_eq!(parse_node(""), None);
+    }
+
+    #[test]
+    fn untrusted_peer_ignores_headers() {
+        let headers = headers(&[("x-forwarded-for", "1.2.3.4")]);
+        assert_eq!(resolve(peer("203.0.113.9"), &headers, &proxies(), ProxyHeader::XForwardedFor), ip("203.0.113.9"));
+    }
+
+    #[test]
+    fn spoofed_hops_left_of_client_are_ignored() {
+        // Client sent "1.2.3.4", nginx appended the real peer
+        let headers = headers(&[("x-forwarded-for", "1.2.3.4, 198.51.100.7")]);
+        assert_eq!(resolve(peer("10.0.0.2"), &headers, &proxies(), ProxyHeader::XForwardedFor), ip("198.51.100.7"));
+    }
+
+    #[test]
+    fn chained_proxies_are_skipped() {
+        let headers = headers(&[("x-forwarded-for", "198.51.100.7, 10.1.1.1"), ("x-forwarded-for", "10.2.2.2")]);
+        assert_eq!(resolve(peer("10.0.0.2"), &headers, &proxies(), ProxyHeader::XForwardedFor), ip("198.51.100.7"));
+    }
+
+    #[test]
+    fn unconfigured_header_is_never_read() {
+        let spoofed = headers(&[("forwarded", "for=1.2.3.4"), ("x-forwarded-for", "198.51.100.7")]);
+        assert_eq!(resolve(peer("10.0.0.2"), &spoofed, &proxies(), ProxyHeader::XForwardedFor), ip("198.51.100.7"));
+
+        let spoofed = headers(&[("x-forwarded-for", "1.2.3.4"), ("forwarded", "for=198.51.100.7")]);
+        assert_eq!(resolve(peer("10.0.0.2"), &spoofed, &proxies(), ProxyHeader::Forwarded), ip("198.51.100.7"));
+
+        // Nothing from the proxy header, the proxy itself is all we know
+        let spoofed = headers(&[("forwarded", "for=1.2.3.4")]);
+        assert_eq!(resolve(peer("10.0.0.2"), &spoofed, &proxies(), ProxyHeader::XForwardedFor), ip("10.0.0.2"));
+    }
+
+    #[test]
+    fn forwarded_ipv6_and_ports() {
+        let headers = headers(&[("forwarded", "for=1.2.3.4, for=\"[2001:db8::1]:4711\";proto=https, For=10.3.3.3:80")]);
+        assert_eq!(resolve(peer("10.0.0.2"), &headers, &proxies(), ProxyHeader::Forwarded), ip("2001:db8::1"));
+    }
+
+    #[test]
+    fn x_forwarded_for_port_suffix() {
+        let headers = headers(&[("x-forwarded-for", "198.51.100.7:5000")]);
+        assert_eq!(resolve(peer("10.0.0.2"), &headers, &proxies(), ProxyHeader::XForwardedFor), ip("198.51.100.7"));
+    }
+
+    #[test]
+    fn all_hops_trusted_falls_back_to_peer() {
+        let headers = headers(&[("x-forwarded-for", "10.9.9.9, garbage")]);
+        assert_eq!(resolve(peer("10.0.0.2"), &headers, &proxies(), ProxyHeader::XForwardedFor), ip("10.0.0.2"));
+        assert_eq!(resolve(None, &headers, &proxies(), ProxyHeader::XForwardedFor), None);
+    }
+}
@@ -0,0 +1,73 @@
+use warp::{Filter, Rejection};
+use warp::path::FullPath;
+use std::env;
+use crate::utils::environment::{self, Environment};
+
+// Matches /api and everything below it without consuming the path
+pub fn api_scope() -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    warp::path::full()
+        .and_then(|path: FullPath| async move {
+            if path.as_str() == "/api" || path.as_str().starts_with("/api/") {
+                Ok(())
+            } else {
+                Err(warp::reject::not_found())
+            }
+        })
+        .untuple_one()
+}
+
+fn allow_credentials() -> bool {
+    env::var("cors_allow_credentials")
+        .map(|value| value == "true")
+        .unwrap_or(false)
+}
+
+fn any_origin(origins: &[String]) -> bool {
+    origins.iter().any(|origin| origin == "*")
+        || (environment::current() == Environment::Development && origins.is_empty())
+}
+
+// Any origin with credentials would let every site make authenticated calls
+pub fn validate() -> Result<(), String> {
+    let origins = environment::list("cors_allowed_origins").unwrap_or_default();
+    if allow_credentials() && any_origin(&origins) {
+        return Err(String::from(
+            "$cors_allow_credentials=true needs explicit $cors_allowed_origins, not any origin"
+        ));
+    }
+    Ok(())
+}
+
+// Development allows any origin, other environments only $cors_allowed_origins
+pub fn cors() -> warp::cors::Builder {
+    validate().expect("Invalid CORS config");
+    let development = environment::current() == Environment::Development;
+
+    let origins = environment::list("cors_allowed_origins").unwrap_or_default();
+    let methods = environment::list("cors_allowed_methods").unwrap_or_else(|| {
+        ["GET", "POST", "PUT", "PATCH", "DELETE"].iter().map(|m| m.to_string()).collect()
+    });
+    let headers = environment::list("cors_allowed_headers").unwrap_or_else(|| {
+        ["authorization", "content-type", "idempotency-key", "x-request-id"].iter().map(|h| h.to_string()).collect()
+    });
+    let credentials = allow_credentials();
+    let max_age = env::var("cors_max_age")
+        .ok()
+        .and_then(|value| value.parse::<u32>().ok())
+        .unwrap_or(if development { 86400 } else { 3600 });
+
+    let mut builder = warp::cors()
+        .allow_methods(methods.iter().map(String::as_str))
+        .allow_headers(headers.iter().map(String::as_str))
+        .expose_headers(vec!["x-request-id", "x-ratelimit-limit", "x-ratelimit-remaining", "x-ratelimit-reset", "idempotent-replayed"])
+        .allow_credentials(credentials)
+        .max_age(max_age);
+
+    if any_origin(&origins) {
+        builder = builder.allow_any_origin();
+    } else {
+        builder = builder.allow_origins(origins.iter().map(String::as_str));
+    }
+
+    builder
+}
@@ -0,0 +1,34 @@
+use warp::Reply;
+use warp::http::StatusCode;
+use warp::http::header::RETRY_AFTER;
+use warp::reply::Response;
+use crate::requests::middleware::access_log;
+use crate::requests::middleware::context::RequestContext;
+
+// A 503 with Retry-After is a deliberate backoff (maintenance mode), not a failure
+fn is_deliberate(response: &Response) -> bool {
+    response.status() == StatusCode::SERVICE_UNAVAILABLE && response.headers().contains_key(RETRY_AFTER)
+}
+
+// Send every other 5xx response to the error tracker with the request context
+pub fn report(context: &RequestContext, request_id: &str, reply: impl Reply) -> Response {
+    let response = reply.into_response();
+    let status = response.status();
+
+    if status.is_server_error() && !is_deliberate(&response) {
+        sentry::with_scope(
+            |scope| {
+                scope.set_tag("method", context.method.as_str());
+                scope.set_tag("route", access_log::path_template(&context.path));
+                scope.set_tag("request_id", request_id);
+                scope.set_extra("query", context.query.clone().into());
+            },
+            || sentry::capture_message(
+                &format!("{} {} responded with {}", context.method, context.path, status),
+                sentry::Level::Error,
+            ),
+        );
+    }
+
+    response
+}
@@ -0,0 +1,157 @@
+use warp::{Filter, Rejection, Reply};
+use warp::http::{HeaderMap, Method, StatusCode};
+use warp::http::header::HeaderValue;
+use warp::hyper::body::{self, Body, HttpBody};
+use warp::path::FullPath;
+use warp::reply::Response;
+use lazy_static::lazy_static;
+use std::convert::Infallible;
+use sha2::{Digest, Sha256};
+use std::env;
+use std::net::SocketAddr;
+use std::time::Duration;
+use tracing::error;
+use crate::requests::routes::test::rejection::ErrorMessage;
+use crate::requests::middleware::context;
+use crate::utils::idempotency_store::{Begin, IdempotencyStore, StoredResponse};
+
+// Larger responses are not kept, a retry runs the handler again
+const MAX_STORED_BODY: u64 = 1024 * 1024;
+
+lazy_static! {
+    // $idempotency_window_secs: how long a key replays its response, default 24h
+    static ref STORE: IdempotencyStore = IdempotencyStore::new(Duration::from_secs(
+        env::var("idempotency_window_secs")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(86400)
+    ));
+}
+
+// Releases the key unless the response was stored, so a dropped connection
+// or a panicking handler leaves the key retryable
+pub struct Reservation {
+    key: String,
+    completed: bool,
+}
+
+impl Drop for Reservation {
+    fn drop(&mut self) {
+        if !self.completed {
+            STORE.release(&self.key);
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum Answered {
+    Replay(StoredResponse),
+    InUse,
+}
+
+impl warp::reject::Reject for Answered {}
+
+// Idempotency-Key of a POST, scoped to the caller and its path. The caller is
+// the client IP plus a digest of Authorization, so a guessed key never
+// replays somebody else's response.
+fn key() -> impl Filter<Extract = (Option<String>,), Error = Infallible> + Clone {
+    warp::method()
+        .and(warp::path::full())
+        .and(context::remote())
+        .and(warp::header::headers_cloned())
+        .map(|method: Method, path: FullPath, remote: Option<SocketAddr>, headers: HeaderMap| {
+            if method != Method::POST {
+                return None;
+            }
+            let key = headers
+                .get("idempotency-key")
+                .and_then(|value| value.to_str().ok())
+                .filter(|key| !key.is_empty() && key.len() <= 255)?;
+            let caller = context::client_ip(remote, &headers).map(|ip| ip.to_string()).unwrap_or_default();
+            let authorization = headers
+                .get("authorization")
+                .map(|value| format!("{:x}", Sha256::digest(value.as_bytes())))
+                .unwrap_or_default();
+            Some(format!("{}:{}:{}:{}", caller, authorization, path.as_str(), key))
+        })
+}
+
+// Reserves the key, retries are rejected with Answered for `answer` to
+// turn into the stored response or a 409
+pub fn begin() -> impl Filter<Extract = (Option<Reservation>,), Error = Rejection> + Clone {
+    key().and_then(|key: Option<String>| async move {
+        let key = match key {
+            Some(key) => key,
+            None => return Ok(None),
+        };
+        match STORE.begin(&key) {
+            Begin::Reserved => Ok(Some(Reservation { key, completed: false })),
+            Begin::InFlight => Err(warp::reject::custom(Answered::InUse)),
+            Begin::Completed(stored) => Err(warp::reject::custom(Answered::Replay(stored))),
+        }
+    })
+}
+
+pub async fn answer(rejection: Rejection) -> Result<Response, Rejection> {
+    match rejection.find::<Answered>() {
+        Some(Answered::Replay(stored)) => {
+            let mut response = Response::new(Body::from(stored.body.clone()));
+            *response.status_mut() = stored.status;
+            *response.headers_mut() = stored.headers.clone();
+            response.headers_mut().insert("idempotent-replayed", HeaderValue::from_static("true"));
+            Ok(response)
+        }
+        Some(Answered::InUse) => {
+            let json = warp::reply::json(&ErrorMessage {
+                code: StatusCode::CONFLICT.as_u16(),
+                message: String::from("IDEMPOTENCY_KEY_IN_USE"),
+            });
+            Ok(warp::reply::with_status(json, StatusCode::CONFLICT).into_response())
+        }
+        None => Err(rejection),
+    }
+}
+
+// Expired keys are otherwise only dropped when a retry comes in
+pub fn purge() -> usize {
+    STORE.purge()
+}
+
+// Keep the finished response for the key, failures stay retryable
+pub async fn store(reservation: Option<Reservation>, reply: impl Reply) -> Response {
+    let response = reply.into_response();
+    let mut reservation = match reservation {
+        Some(reservation) => reservation,
+        None => return response,
+    };
+
+    let status = response.status();
+    let storable = !status.is_server_error()
+        && status != StatusCode::TOO_MANY_REQUESTS
+        && response.body().size_hint().exact().is_some_and(|size| size <= MAX_STORED_BODY);
+    if !storable {
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    match body::to_bytes(body).await {
+        Ok(bytes) => {
+            // Rate limit state belongs to the original request, stale on a replay
+            let mut headers = parts.headers.clone();
+            for name in ["x-ratelimit-limit", "x-ratelimit-remaining", "x-ratelimit-reset"] {
+                headers.remove(name);
+            }
+            STORE.complete(&reservation.key, StoredResponse {
+                status: parts.status,
+                headers,
+                body: bytes.clone(),
+            });
+            reservation.completed = true;
+            Response::from_parts(parts, Body::from(bytes))
+        }
+        Err(e) => {
+            error!("Failed to buffer response body: {}", e);
+            Response::from_parts(parts, Body::empty())
+        }
+    }
+}
@@ -0,0 +1,223 @@
+use warp::http::{Method, StatusCode};
+use warp::http::header::CONTENT_LENGTH;
+use warp::hyper::body::{self, Body};
+use warp::reply::Response;
+use serde_json::{Map, Value};
+use tracing::error;
+use crate::requests::middleware::context::RequestContext;
+use crate::requests::middleware::openapi_validation;
+use crate::requests::routes::version::{self, KeyCase};
+
+fn camel_case(key: &str) -> String {
+    let mut result = String::with_capacity(key.len());
+    let mut upper = false;
+    for c in key.chars() {
+        if c == '_' && !result.is_empty() {
+            upper = true;
+        } else if upper {
+            result.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+fn recase(key: String, case: KeyCase) -> String {
+    match case {
+        KeyCase::Snake => key,
+        KeyCase::Camel => camel_case(&key),
+    }
+}
+
+// Follows #/components/schemas/.. references
+fn resolve(schema: &'static Value) -> &'static Value {
+    match schema.get("$ref").and_then(Value::as_str).and_then(|reference| reference.strip_prefix('#')) {
+        Some(pointer) => openapi_validation::document().pointer(pointer).map_or(schema, resolve),
+        None => schema,
+    }
+}
+
+// First match in the schema or any of its allOf/oneOf/anyOf parts
+fn find(schema: &'static Value, lookup: &dyn Fn(&'static Value) -> Option<&'static Value>) -> Option<&'static Value> {
+    let schema = resolve(schema);
+    lookup(schema).or_else(|| {
+        ["allOf", "oneOf", "anyOf"]
+            .into_iter()
+            .filter_map(|combinator| schema.get(combinator).and_then(Value::as_array))
+            .flatten()
+            .find_map(|part| find(part, lookup))
+    })
+}
+
+// Only keys the schema declares as properties are DTO field names. Map keys
+// (additionalProperties) and undocumented objects are data and stay as sent.
+fn convert(value: Value, schema: &'static Value, case: KeyCase) -> Value {
+    match value {
+        Value::Object(object) => Value::Object(
+            object
+                .into_iter()
+                .map(|(key, value)| {
+                    if let Some(property) = find(schema, &|schema| schema.get("properties")?.get(&key)) {
+                        (recase(key, case), convert(value, property, case))
+                    } else if let Some(additional) = find(schema, &|schema| schema.get("additionalProperties").filter(|value| value.is_object())) {
+                        (key, convert(value, additional, case))
+                    } else {
+                        (key, value)
+                    }
+                })
+                .collect::<Map<String, Value>>()
+        ),
+        Value::Array(items) => match find(schema, &|schema| schema.get("items")) {
+            Some(item) => Value::Array(items.into_iter().map(|value| convert(value, item, case)).collect()),
+            None => Value::Array(items),
+        },
+        value => value,
+    }
+}
+
+// Re-keys a JSON body for the version in `path`, guided by the documented
+// response schema. Batch sub-responses go through here too.
+pub fn convert_body(method: &Method, path: &str, status: StatusCode, value: Value) -> Value {
+    let case = match version::from_path(path) {
+        Some(version) => version.key_case(),
+        None => return value,
+    };
+    let method = if method == Method::HEAD { &Method::GET } else { method };
+    match openapi_validation::response_schema(method, path, status) {
+        Some(schema) if case != KeyCase::Snake => convert(value, schema, case),
+        _ => value,
+    }
+}
+
+// Schema property names (and their required lists) in the case of the
+// version, for the per-version OpenAPI document
+pub fn convert_document(value: Value, case: KeyCase) -> Value {
+    match value {
+        Value::Object(object) => {
+            let is_schema = object.get("properties").is_some_and(Value::is_object);
+            Value::Object(
+                object
+                    .into_iter()
+                    .map(|(key, value)| match (is_schema, key.as_str(), value) {
+                        (true, "properties", Value::Object(properties)) => (key, Value::Object(
+                            properties
+                                .into_iter()
+                                .map(|(name, schema)| (recase(name, case), convert_document(schema, case)))
+                                .collect()
+                        )),
+                        (true, "required", Value::Array(names)) => (key, Value::Array(
+                            names
+                                .into_iter()
+                                .map(|name| match name {
+                                    Value::String(name) => Value::String(recase(name, case)),
+                                    name => name,
+                                })
+                                .collect()
+                        )),
+                        (_, _, value) => (key, convert_document(value, case)),
+                    })
+                    .collect()
+            )
+        }
+        Value::Array(items) => Value::Array(items.into_iter().map(|item| convert_document(item, case)).collect()),
+        value => value,
+    }
+}
+
+// DTOs stay snake_case, JSON responses are re-keyed for versions that want
+// another case. Anything outside /api/{version} is left alone, and so is the
+// OpenAPI document, it is generated per version.
+pub async fn apply(context: &RequestContext, response: Response) -> Response {
+    if context.path.ends_with("/openapi.json") {
+        return response;
+    }
+    let snake = version::from_path(&context.path).is_none_or(|version| version.key_case() == KeyCase::Snake);
+    if snake || !openapi_validation::is_json(response.headers()) {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match body::to_bytes(body).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            error!("Failed to buffer response body: {}", e);
+            return Response::from_parts(parts, Body::empty());
+        }
+    };
+    let converted = match serde_json::from_slice::<Value>(&bytes) {
+        Ok(value) => {
+            let value = convert_body(&context.method, &context.path, parts.status, value);
+            serde_json::to_vec(&value).unwrap_or_else(|_| bytes.to_vec())
+        }
+        Err(_) => bytes.to_vec(),
+    };
+
+    parts.headers.remove(CONTENT_LENGTH);
+    Response::from_parts(parts, Body::from(converted))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn schema(value: Value) -> &'static Value {
+        Box::leak(Box::new(value))
+    }
+
+    fn limits() -> Value {
+        json!({
+            "client": "ip:192.0.2.1",
+            "quotas": [{"bucket": "global", "limit": 100, "period_secs": 60, "remaining": 99, "reset": 60}],
+        })
+    }
+
+    #[test]
+    fn camel_case_keys() {
+        assert_eq!(camel_case("period_secs"), "periodSecs");
+        assert_eq!(camel_case("created_at_utc"), "createdAtUtc");
+        assert_eq!(camel_case("_private"), "_private");
+        assert_eq!(camel_case("plain"), "plain");
+    }
+
+    #[test]
+    fn nested_objects_and_arrays() {
+        let schema = schema(json!({
+            "properties": {
+                "painting_id": {"type": "string"},
+                "price_stats": {"properties": {"min_price": {}, "max_price": {}}},
+                "sold_items": {"items": {"properties": {"sold_at": {}, "buyer_country": {}}}},
+                "by_currency": {"additionalProperties": {"properties": {"total_amount": {}}}},
+            },
+        }));
+        let value = json!({
+            "painting_id": "a_b",
+            "price_stats": {"min_price": 1, "max_price": 2},
+            "sold_items": [{"sold_at": "2024-01-01", "buyer_country": "CZ", "extra_key": 1}],
+            "by_currency": {"CZK_cash": {"total_amount": 10}},
+            "undocumented_key": {"nested_key": true},
+        });
+        assert_eq!(convert(value, schema, KeyCase::Camel), json!({
+            "paintingId": "a_b",
+            "priceStats": {"minPrice": 1, "maxPrice": 2},
+            "soldItems": [{"soldAt": "2024-01-01", "buyerCountry": "CZ", "extra_key": 1}],
+            "byCurrency": {"CZK_cash": {"totalAmount": 10}},
+            "undocumented_key": {"nested_key": true},
+        }));
+    }
+
+    #[test]
+    fn v2_body_follows_the_documented_schema() {
+        let value = convert_body(&Method::GET, "/api/v2.0/limits", StatusCode::OK, limits());
+        assert_eq!(value["quotas"][0]["periodSecs"], 60);
+        assert!(value["quotas"][0].get("period_secs").is_none());
+    }
+
+    #[test]
+    fn v1_body_passes_through() {
+        assert_eq!(convert_body(&Method::GET, "/api/v1.0/limits", StatusCode::OK, limits()), limits());
+        assert_eq!(convert_body(&Method::GET, "/healthz", StatusCode::OK, limits()), limits());
+    }
+}
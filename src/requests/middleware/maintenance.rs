@@ -0,0 +1,75 @@
+use warp::{Filter, Rejection};
+use warp::http::StatusCode;
+use warp::http::header::{HeaderValue, RETRY_AFTER};
+use warp::path::FullPath;
+use warp::reply::{Reply, Response};
+use lazy_static::lazy_static;
+use serde_derive::Serialize;
+use std::env;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+lazy_static! {
+    // $maintenance_mode=true starts the API in maintenance
+    static ref ENABLED: AtomicBool = AtomicBool::new(
+        env::var("maintenance_mode").is_ok_and(|value| value == "true")
+    );
+    // $maintenance_retry_after_secs: Retry-After sent with the 503, default 5 minutes
+    static ref RETRY_AFTER_SECS: u64 = env::var("maintenance_retry_after_secs")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(300);
+}
+
+// Probes keep answering so the orchestrator doesn't restart the instance
+const EXEMPT_PATHS: [&str; 3] = ["/healthz", "/readyz", "/livez"];
+
+#[derive(Debug)]
+pub struct UnderMaintenance;
+
+impl warp::reject::Reject for UnderMaintenance {}
+
+#[derive(Serialize)]
+struct MaintenanceMessage {
+    code: u16,
+    message: String,
+    cs: String,
+    en: String,
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+// For an admin toggle, flips the mode without a restart
+#[allow(dead_code)]
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn check() -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    warp::path::full()
+        .and_then(|path: FullPath| async move {
+            if is_enabled() && !EXEMPT_PATHS.contains(&path.as_str()) {
+                Err(warp::reject::custom(UnderMaintenance))
+            } else {
+                Ok(())
+            }
+        })
+        .untuple_one()
+}
+
+// 503 with Retry-After and the message in both site languages
+pub fn response() -> Response {
+    let json = warp::reply::json(&MaintenanceMessage {
+        code: StatusCode::SERVICE_UNAVAILABLE.as_u16(),
+        message: String::from("MAINTENANCE"),
+        cs: env::var("maintenance_message_cs")
+            .unwrap_or_else(|_| String::from("Probíhá údržba, zkuste to prosím později.")),
+        en: env::var("maintenance_message_en")
+            .unwrap_or_else(|_| String::from("Down for maintenance, please try again later.")),
+    });
+
+    let mut response = warp::reply::with_status(json, StatusCode::SERVICE_UNAVAILABLE).into_response();
+    response.headers_mut().insert(RETRY_AFTER, HeaderValue::from(*RETRY_AFTER_SECS));
+    response
+}
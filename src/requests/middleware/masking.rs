@@ -0,0 +1,190 @@
+use warp::http::header::CONTENT_LENGTH;
+use warp::hyper::body::{self, Body};
+use warp::reply::Response;
+use lazy_static::lazy_static;
+use serde_json::{Map, Value};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::env;
+use tracing::error;
+use crate::requests::middleware::context::RequestContext;
+use crate::requests::middleware::openapi_validation;
+use crate::utils::environment;
+
+lazy_static! {
+    // $data_masking=true for preview instances running on a production snapshot
+    static ref ENABLED: bool = env::var("data_masking").is_ok_and(|value| value == "true");
+    // $data_masking_secret keys the email hashes, required when masking is on
+    static ref SECRET: Option<String> = env::var("data_masking_secret").ok().filter(|secret| !secret.is_empty());
+    // $data_masking_keys: every string under these keys is personal data of a buyer
+    static ref BUYER_KEYS: Vec<String> = environment::list("data_masking_keys")
+        .unwrap_or_else(|| ["buyer", "customer", "phone", "address"].iter().map(|key| key.to_string()).collect());
+}
+
+// Without a secret, hashes of known address lists would unmask the emails
+pub fn validate() -> Result<(), String> {
+    if *ENABLED && SECRET.is_none() {
+        return Err(String::from("$data_masking=true needs $data_masking_secret"));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Scope {
+    Plain,
+    Price,
+    Buyer,
+}
+
+fn scope_of(key: &str, parent: Scope) -> Scope {
+    if parent != Scope::Plain {
+        parent
+    } else if key == "price" || key == "prices" || key.ends_with("_price") {
+        Scope::Price
+    } else if BUYER_KEYS.iter().any(|buyer_key| buyer_key == key) {
+        Scope::Buyer
+    } else {
+        Scope::Plain
+    }
+}
+
+// 3450 -> "3000-3999", one significant digit is all a preview gets to see
+fn price_range(price: f64) -> String {
+    if price < 1.0 {
+        return String::from("0-0");
+    }
+    let step = 10f64.powi(price.log10().floor() as i32);
+    let lower = (price / step).floor() * step;
+    format!("{}-{}", lower as i64, (lower + step) as i64 - 1)
+}
+
+fn is_email(value: &str) -> bool {
+    match value.split_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty()
+                && domain.contains('.')
+                && !domain.contains('@')
+                && !value.contains(char::is_whitespace)
+        }
+        None => false,
+    }
+}
+
+// Stable, so the same buyer still shows up as the same address
+fn hash_email(value: &str, secret: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(value.to_lowercase().as_bytes());
+    let digest: String = mac.finalize().into_bytes().iter().map(|byte| format!("{:02x}", byte)).collect();
+    format!("{}@masked.invalid", &digest[..16])
+}
+
+fn mask(value: Value, scope: Scope, secret: &str) -> Value {
+    match value {
+        Value::Object(object) => Value::Object(
+            object
+                .into_iter()
+                .map(|(key, value)| {
+                    let scope = scope_of(&key, scope);
+                    (key, mask(value, scope, secret))
+                })
+                .collect::<Map<String, Value>>()
+        ),
+        Value::Array(items) => Value::Array(items.into_iter().map(|item| mask(item, scope, secret)).collect()),
+        Value::Number(number) if scope == Scope::Price => {
+            Value::String(price_range(number.as_f64().unwrap_or_default()))
+        }
+        // NUMERIC columns come out of the database as strings
+        Value::String(text) if scope == Scope::Price && text.trim().parse::<f64>().is_ok_and(f64::is_finite) => {
+            Value::String(price_range(text.trim().parse().unwrap_or_default()))
+        }
+        Value::String(text) if is_email(&text) => Value::String(hash_email(&text, secret)),
+        Value::String(_) if scope == Scope::Buyer => Value::String(String::from("redacted")),
+        value => value,
+    }
+}
+
+// Preview mode: prices become ranges, buyer data is redacted and emails are
+// hashed in every JSON response. Runs before json_case, keys are still
+// snake_case here. The OpenAPI document is left alone.
+pub async fn apply(context: &RequestContext, response: Response) -> Response {
+    if !*ENABLED || context.path.ends_with("/openapi.json") || !openapi_validation::is_json(response.headers()) {
+        return response;
+    }
+    let secret = match SECRET.as_deref() {
+        Some(secret) => secret,
+        None => return response,
+    };
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match body::to_bytes(body).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            error!("Failed to buffer response body: {}", e);
+            return Response::from_parts(parts, Body::empty());
+        }
+    };
+    let masked = match serde_json::from_slice::<Value>(&bytes) {
+        Ok(value) => serde_json::to_vec(&mask(value, Scope::Plain, secret)).unwrap_or_else(|_| bytes.to_vec()),
+        Err(_) => bytes.to_vec(),
+    };
+
+    parts.headers.remove(CONTENT_LENGTH);
+    Response::from_parts(parts, Body::from(masked))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const SECRET: &str = "test-secret";
+
+    #[test]
+    fn price_ranges() {
+        assert_eq!(price_range(3450.0), "3000-3999");
+        assert_eq!(price_range(12.5), "10-19");
+        assert_eq!(price_range(100000.0), "100000-199999");
+        assert_eq!(price_range(0.5), "0-0");
+    }
+
+    #[test]
+    fn nested_prices_are_ranged() {
+        let value = json!({
+            "title": "Lake",
+            "price": {"amount": 3450, "currency": "CZK", "history": [1200, "870.50"]},
+            "shipping_price": "250",
+            "year": 1987,
+        });
+        assert_eq!(mask(value, Scope::Plain, SECRET), json!({
+            "title": "Lake",
+            "price": {"amount": "3000-3999", "currency": "CZK", "history": ["1000-1999", "800-899"]},
+            "shipping_price": "200-299",
+            "year": 1987,
+        }));
+    }
+
+    #[test]
+    fn buyer_fields_are_redacted() {
+        let value = json!({
+            "buyer": {"name": "Jan Novák", "email": "jan@example.cz", "orders": [{"note": "fragile"}], "id": 7},
+            "phone": "+420 123 456 789",
+            "contact": "JAN@example.cz",
+            "title": "Lake",
+        });
+        let hashed = hash_email("jan@example.cz", SECRET);
+        assert_eq!(mask(value, Scope::Plain, SECRET), json!({
+            "buyer": {"name": "redacted", "email": hashed, "orders": [{"note": "redacted"}], "id": 7},
+            "phone": "redacted",
+            "contact": hashed,
+            "title": "Lake",
+        }));
+    }
+
+    #[test]
+    fn email_hash_is_keyed() {
+        let hashed = hash_email("jan@example.cz", SECRET);
+        assert!(hashed.ends_with("@masked.invalid"));
+        assert_eq!(hashed.len(), 16 + "@masked.invalid".len());
+        assert_ne!(hashed, hash_email("jan@example.cz", "other-secret"));
+    }
+}
@@ -0,0 +1,167 @@
+use warp::http::{HeaderMap, Method, StatusCode};
+use warp::http::header::CONTENT_TYPE;
+use warp::hyper::body::{self, Body};
+use warp::reply::Response;
+use jsonschema::Validator;
+use lazy_static::lazy_static;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::env;
+use std::sync::{Arc, Mutex};
+use tracing::{error, warn};
+use utoipa::OpenApi;
+use crate::requests::middleware::context::RequestContext;
+use crate::requests::openapi::ApiDoc;
+use crate::requests::routes::options;
+
+lazy_static! {
+    static ref DOCUMENT: Value = serde_json::to_value(ApiDoc::openapi()).unwrap();
+    static ref TEMPLATES: Vec<(String, Vec<String>)> = DOCUMENT["paths"]
+        .as_object()
+        .map(|paths| paths.keys().map(|template| (template.clone(), template.split('/').map(String::from).collect())).collect())
+        .unwrap_or_default();
+    // Compiled per schema location, None when the document has no JSON schema there
+    static ref VALIDATORS: Mutex<HashMap<String, Option<Arc<Validator>>>> = Mutex::new(HashMap::new());
+}
+
+// Debug builds only, $openapi_validation=false turns it off there too
+pub fn enabled() -> bool {
+    cfg!(debug_assertions) && env::var("openapi_validation").map_or(true, |value| value != "false")
+}
+
+pub fn is_json(headers: &HeaderMap) -> bool {
+    headers
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"))
+}
+
+fn template(path: &str) -> Option<&'static str> {
+    TEMPLATES
+        .iter()
+        .find(|(_, segments)| options::matches(segments, path))
+        .map(|(template, _)| template.as_str())
+}
+
+// Schema at the JSON pointer, with the document's components alongside so
+// #/components/schemas/.. references resolve
+fn validator(pointer: &str) -> Option<Arc<Validator>> {
+    let mut validators = VALIDATORS.lock().unwrap();
+    validators
+        .entry(pointer.to_string())
+        .or_insert_with(|| {
+            let mut schema = DOCUMENT.pointer(pointer)?.clone();
+            schema.as_object_mut()?.insert(String::from("components"), DOCUMENT["components"].clone());
+            match jsonschema::draft202012::new(&schema) {
+                Ok(validator) => Some(Arc::new(validator)),
+                Err(e) => {
+                    error!("Invalid schema at {}: {}", pointer, e);
+                    None
+                }
+            }
+        })
+        .clone()
+}
+
+fn check(kind: &str, method: &Method, template: &str, pointer: &str, body: &[u8]) {
+    let validator = match validator(pointer) {
+        Some(validator) => validator,
+        None => return,
+    };
+    let instance: Value = match serde_json::from_slice(body) {
+        Ok(instance) => instance,
+        Err(e) => {
+            warn!(target: "api::openapi", method = %method, path = template, "{} body is not JSON: {}", kind, e);
+            return;
+        }
+    };
+
+    let errors: Vec<String> = validator
+        .iter_errors(&instance)
+        .map(|e| format!("{} at {}", e, e.instance_path))
+        .collect();
+    if !errors.is_empty() {
+        warn!(target: "api::openapi", method = %method, path = template, errors = ?errors, "{} does not match the OpenAPI schema", kind);
+    }
+}
+
+// Pointer segments escape / as ~1
+fn escape(segment: &str) -> String {
+    segment.replace('~', "~0").replace('/', "~1")
+}
+
+pub fn check_request(method: &Method, path: &str, body: &[u8]) {
+    if let Some(template) = template(path) {
+        let pointer = format!(
+            "/paths/{}/{}/requestBody/content/application~1json/schema",
+            escape(template),
+            method.as_str().to_lowercase()
+        );
+        check("Request", method, template, &pointer, body);
+    }
+}
+
+pub fn document() -> &'static Value {
+    &DOCUMENT
+}
+
+// Pointer to the documented responses of the operation, and the status key
+// (or "default") matching `status`
+fn documented_response(method: &Method, template: &str, status: StatusCode) -> (String, Option<String>) {
+    let operation = format!("/paths/{}/{}/responses", escape(template), method.as_str().to_lowercase());
+    let documented = DOCUMENT
+        .pointer(&operation)
+        .and_then(|responses| responses.as_object())
+        .and_then(|responses| {
+            [status.as_str(), "default"].into_iter().find(|key| responses.contains_key(*key))
+        })
+        .map(String::from);
+    (operation, documented)
+}
+
+// JSON schema of the documented response, None when there is none
+pub fn response_schema(method: &Method, path: &str, status: StatusCode) -> Option<&'static Value> {
+    let template = template(path)?;
+    match documented_response(method, template, status) {
+        (operation, Some(key)) => DOCUMENT.pointer(&format!("{}/{}/content/application~1json/schema", operation, key)),
+        (_, None) => None,
+    }
+}
+
+fn check_response_body(method: &Method, path: &str, status: StatusCode, body: &[u8]) {
+    let template = match template(path) {
+        Some(template) => template,
+        None => return,
+    };
+
+    match documented_response(method, template, status) {
+        (operation, Some(key)) => {
+            let pointer = format!("{}/{}/content/application~1json/schema", operation, key);
+            check("Response", method, template, &pointer, body);
+        }
+        (operation, None) if DOCUMENT.pointer(&operation).is_some() => {
+            warn!(target: "api::openapi", method = %method, path = template, status = status.as_u16(), "Response status is not documented");
+        }
+        (_, None) => {}
+    }
+}
+
+// Buffers JSON responses to validate them, other responses pass untouched
+pub async fn check_response(context: &RequestContext, response: Response) -> Response {
+    if !enabled() || !is_json(response.headers()) {
+        return response;
+    }
+
+    let method = if context.method == Method::HEAD { Method::GET } else { context.method.clone() };
+    let (parts, body) = response.into_parts();
+    match body::to_bytes(body).await {
+        Ok(bytes) => {
+            check_response_body(&method, &context.path, parts.status, &bytes);
+            Response::from_parts(parts, Body::from(bytes))
+        }
+        Err(e) => {
+            error!("Failed to buffer response body: {}", e);
+            Response::from_parts(parts, Body::empty())
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use warp::{Filter, Rejection, Reply};
+use warp::http::HeaderMap;
+use warp::http::header::HeaderValue;
+use warp::path::FullPath;
+use warp::reply::Response;
+use lazy_static::lazy_static;
+use std::net::SocketAddr;
+use crate::requests::middleware::context;
+use crate::requests::routes::version;
+use crate::utils::environment;
+use crate::utils::rate_limiter::{Decision, Policy, RateLimiter};
+
+lazy_static! {
+    static ref LIMITER: RateLimiter = RateLimiter::new();
+    static ref GLOBAL: Policy = Policy::from_env("rate_limit", 100, 60);
+    static ref STRICT: Policy = Policy::from_env("rate_limit_strict", 10, 60);
+    // $rate_limit_strict_paths: path prefixes limited by the strict policy,
+    // /api/{version}/auth of every mounted version by default
+    static ref STRICT_PATHS: Vec<String> = environment::list("rate_limit_strict_paths")
+        .unwrap_or_else(|| version::ALL.iter().map(|v| format!("/api/{}/auth", v)).collect());
+}
+
+// Probes must never be throttled
+const EXEMPT_PATHS: [&str; 3] = ["/healthz", "/readyz", "/livez"];
+
+#[derive(Debug)]
+pub struct RateLimited {
+    pub decision: Decision,
+}
+
+impl warp::reject::Reject for RateLimited {}
+
+// Client IP only: X-Api-Key isn't validated anywhere, keying on it would
+// hand out a fresh bucket per made-up key
+fn key(remote: Option<SocketAddr>, headers: &HeaderMap) -> String {
+    match context::client_ip(remote, headers) {
+        Some(ip) => format!("ip:{}", ip),
+        None => String::from("ip:unknown"),
+    }
+}
+
+// Token bucket per client IP
+pub fn limit() -> impl Filter<Extract = (Option<Decision>,), Error = Rejection> + Clone {
+    warp::path::full()
+        .and(context::remote())
+        .and(warp::header::headers_cloned())
+        .and_then(|path: FullPath, remote: Option<SocketAddr>, headers: HeaderMap| async move {
+            let path = path.as_str();
+            if EXEMPT_PATHS.contains(&path) {
+                return Ok(None);
+            }
+
+            let (bucket, policy) = if STRICT_PATHS.iter().any(|prefix| path.starts_with(prefix.as_str())) {
+                ("strict", &*STRICT)
+            } else {
+                ("global", &*GLOBAL)
+            };
+            let decision = LIMITER.check(&format!("{}:{}", bucket, key(remote, &headers)), policy);
+
+            if decision.allowed {
+                Ok(Some(decision))
+            } else {
+                Err(warp::reject::custom(RateLimited { decision }))
+            }
+        })
+}
+
+// Both buckets of the caller, for clients that want to back off early
+pub fn quota(remote: Option<SocketAddr>, headers: &HeaderMap) -> Vec<(&'static str, Policy, Decision)> {
+    let key = key(remote, headers);
+    [("global", *GLOBAL), ("strict", *STRICT)]
+        .into_iter()
+        .map(|(bucket, policy)| (bucket, policy, LIMITER.peek(&format!("{}:{}", bucket, key), &policy)))
+        .collect()
+}
+
+// Full buckets are otherwise kept forever
+pub fn purge() -> usize {
+    LIMITER.purge()
+}
+
+pub fn apply_headers(headers: &mut HeaderMap, decision: &Decision) {
+    headers.insert("x-ratelimit-limit", HeaderValue::from(decision.limit));
+    headers.insert("x-ratelimit-remaining", HeaderValue::from(decision.remaining));
+    headers.insert("x-ratelimit-reset", HeaderValue::from(decision.reset));
+}
+
+pub fn headers(decision: Option<Decision>, reply: impl Reply) -> Response {
+    let mut response = reply.into_response();
+    if let Some(decision) = decision {
+        apply_headers(response.headers_mut(), &decision);
+    }
+    response
+}
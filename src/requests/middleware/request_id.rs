@@ -0,0 +1,51 @@
+use warp::{Filter, Rejection, Reply};
+use warp::http::HeaderMap;
+use warp::http::header::HeaderValue;
+use warp::trace::{Info, Trace};
+use tracing::{field, info_span, Span};
+use uuid::Uuid;
+
+pub const HEADER: &str = "x-request-id";
+
+fn is_valid(id: &str) -> bool {
+    !id.is_empty() && id.len() <= 128 && id.bytes().all(|b| b.is_ascii_graphic())
+}
+
+// Sets a generated id on requests without a valid one, so it is known
+// before the filters run
+pub fn ensure(headers: &mut HeaderMap) -> String {
+    if let Some(id) = headers.get(HEADER).and_then(|value| value.to_str().ok()).filter(|id| is_valid(id)) {
+        return id.to_string();
+    }
+    let id = Uuid::new_v4().to_string();
+    headers.insert(HEADER, HeaderValue::from_str(&id).unwrap());
+    id
+}
+
+// Accept a sane X-Request-Id from the client, otherwise generate one,
+// and record it on the request span so every log line carries it
+pub fn request_id() -> impl Filter<Extract = (String,), Error = Rejection> + Clone {
+    warp::header::optional::<String>(HEADER)
+        .map(|header: Option<String>| {
+            let id = header
+                .filter(|id| is_valid(id))
+                .unwrap_or_else(|| Uuid::new_v4().to_string());
+            Span::current().record("request_id", id.as_str());
+            id
+        })
+}
+
+pub fn echo(id: String, reply: impl Reply) -> impl Reply {
+    warp::reply::with_header(reply, HEADER, id)
+}
+
+pub fn span() -> Trace<impl Fn(Info) -> Span + Clone> {
+    warp::trace(|info: Info| {
+        info_span!(
+            "request",
+            method = %info.method(),
+            path = %info.path(),
+            request_id = field::Empty,
+        )
+    })
+}
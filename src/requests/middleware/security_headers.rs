@@ -0,0 +1,89 @@
+use warp::http::HeaderMap;
+use warp::http::header::{
+    HeaderName, HeaderValue, CONTENT_SECURITY_POLICY, CONTENT_SECURITY_POLICY_REPORT_ONLY, CONTENT_TYPE,
+    REFERRER_POLICY, STRICT_TRANSPORT_SECURITY, X_CONTENT_TYPE_OPTIONS, X_FRAME_OPTIONS,
+};
+use warp::reply::Response;
+use lazy_static::lazy_static;
+use std::env;
+use crate::utils::environment::{self, Environment};
+
+// Enough for the Swagger UI: its inline styles and data: icons
+const DEFAULT_CSP: &str = "default-src 'self'; img-src 'self' data:; style-src 'self' 'unsafe-inline'; frame-ancestors 'none'";
+
+lazy_static! {
+    static ref HEADERS: SecurityHeaders = SecurityHeaders::from_env();
+}
+
+struct SecurityHeaders {
+    // On every response
+    common: Vec<(HeaderName, HeaderValue)>,
+    // On text/html only
+    html: Vec<(HeaderName, HeaderValue)>,
+}
+
+// $<name> overrides the default, "off" drops the header
+fn setting(name: &str, default: Option<String>) -> Option<HeaderValue> {
+    let value = env::var(name).ok().or(default)?;
+    if value == "off" {
+        return None;
+    }
+    HeaderValue::from_str(&value).ok()
+}
+
+impl SecurityHeaders {
+    fn from_env() -> SecurityHeaders {
+        let environment = environment::current();
+
+        // HSTS only where TLS is certain, $hsts_max_age=0 turns it off
+        let hsts_max_age = env::var("hsts_max_age")
+            .ok()
+            .and_then(|value| value.parse::<u64>().ok())
+            .unwrap_or(if environment == Environment::Production { 31536000 } else { 0 });
+
+        let mut common = vec![(X_CONTENT_TYPE_OPTIONS, HeaderValue::from_static("nosniff"))];
+        if let Some(value) = setting("referrer_policy", Some(String::from("strict-origin-when-cross-origin"))) {
+            common.push((REFERRER_POLICY, value));
+        }
+        if hsts_max_age > 0 {
+            common.push((STRICT_TRANSPORT_SECURITY, HeaderValue::from_str(&format!("max-age={}; includeSubDomains", hsts_max_age)).unwrap()));
+        }
+
+        let mut html = Vec::new();
+        if let Some(value) = setting("content_security_policy", Some(String::from(DEFAULT_CSP))) {
+            // Development reports violations without breaking local tooling
+            let name = if environment == Environment::Development {
+                CONTENT_SECURITY_POLICY_REPORT_ONLY
+            } else {
+                CONTENT_SECURITY_POLICY
+            };
+            html.push((name, value));
+        }
+        if let Some(value) = setting("frame_options", Some(String::from("DENY"))) {
+            html.push((X_FRAME_OPTIONS, value));
+        }
+
+        SecurityHeaders { common, html }
+    }
+}
+
+fn insert_missing(headers: &mut HeaderMap, values: &[(HeaderName, HeaderValue)]) {
+    for (name, value) in values {
+        headers.entry(name.clone()).or_insert_with(|| value.clone());
+    }
+}
+
+// Headers a handler already set are left alone
+pub fn apply(mut response: Response) -> Response {
+    let is_html = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("text/html"));
+
+    insert_missing(response.headers_mut(), &HEADERS.common);
+    if is_html {
+        insert_missing(response.headers_mut(), &HEADERS.html);
+    }
+    response
+}
@@ -0,0 +1,42 @@
+use utoipa::OpenApi;
+use crate::requests::dto;
+use crate::requests::routes;
+
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "rest_api"),
+    paths(
+        routes::test::salute::get_salute,
+        routes::test::promote::post_promote,
+        routes::test::file::post_file,
+        routes::test::upload::post_upload,
+        routes::health::healthz::get_healthz,
+        routes::health::readyz::get_readyz,
+        routes::health::livez::get_livez,
+        routes::site::robots::get_robots_txt,
+        routes::site::security_txt::get_security_txt,
+        routes::batch::post_batch,
+        routes::paintings::stats::get_painting_stats,
+        routes::paintings::qr::get_painting_qr,
+        routes::limits::get_limits,
+        routes::docs::openapi_json::get_openapi_json,
+        routes::docs::swagger_ui::get_swagger_ui,
+    ),
+    components(schemas(
+        dto::employee::Employee,
+        dto::salute_you::SaluteYou,
+        dto::health::HealthReport,
+        dto::health::HealthCheck,
+        dto::batch::BatchRequest,
+        dto::batch::BatchResponse,
+        dto::painting_stats::PaintingStats,
+        dto::painting_stats::PriceStats,
+        dto::painting_stats::Range,
+        dto::qr::Ecc,
+        dto::qr::QrParams,
+        dto::limits::Limits,
+        dto::limits::Quota,
+        routes::test::rejection::ErrorMessage,
+    )),
+)]
+pub struct ApiDoc;
@@ -1,7 +1,23 @@
-use warp::Filter;
+use warp::{Filter, Rejection, Reply};
 use crate::requests;
+use crate::requests::routes::method;
+use crate::requests::middleware::{access_log, compression, context, cors, error_report, idempotency, json_case, maintenance, masking, openapi_validation, rate_limit, request_id, security_headers};
+
+// Every endpoint except POST /api/{version}/batch
+fn endpoints() -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    // /api/*, CORS applied
+    let api = cors::api_scope().and(
+        // GET /api/{v1.0,v2.0}/openapi.json, /api/docs
+        requests::routes::docs::openapi_json::get()
+        .or(requests::routes::docs::swagger_ui::get())
+        // GET /api/{v1.0,v2.0}/paintings/stats, /paintings/{id}/qr.png
+        .or(requests::routes::paintings::stats::get())
+        .or(requests::routes::paintings::qr::get())
+        // GET /api/{v1.0,v2.0}/limits
+        .or(requests::routes::limits::get())
+        .with(cors::cors())
+    );
 
-pub fn router() -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
     // GET /salute
     requests::routes::test::salute::get()
     // POST /promote
@@ -10,6 +26,81 @@ pub fn router() -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejecti
     .or(requests::routes::test::file::post())
     // POST /upload
     .or(requests::routes::test::upload::post())
-    // Error handling
+    // GET /healthz, /readyz, /livez
+    .or(requests::routes::health::healthz::get())
+    .or(requests::routes::health::readyz::get())
+    .or(requests::routes::health::livez::get())
+    // GET /robots.txt, /.well-known/security.txt
+    .or(requests::routes::site::robots::get())
+    .or(requests::routes::site::security_txt::get())
+    .or(api)
+    // OPTIONS on any documented path
+    .or(requests::routes::options::options())
+    // 404, or 405 with Allow
+    .or(requests::routes::options::fallback())
+}
+
+// Maintenance mode, rate limiting, X-RateLimit-* headers on every limited
+// response including errors, error handling
+fn handled<F, R>(endpoints: F) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone
+where
+    F: Filter<Extract = (R,), Error = Rejection> + Clone + Send + Sync + 'static,
+    R: Reply,
+{
+    maintenance::check()
+    .and(rate_limit::limit())
+    .and(
+        endpoints
+        // Error handling
+        .recover(requests::routes::test::not_found::handle_not_found)
+        .recover(requests::routes::test::rejection::handle_rejection)
+    )
+    .map(rate_limit::headers)
+    // Maintenance and rate limit rejections
     .recover(requests::routes::test::not_found::handle_not_found)
-}
\ No newline at end of file
+    .recover(requests::routes::test::rejection::handle_rejection)
+}
+
+// What batch sub-requests are dispatched to
+fn routes() -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    handled(endpoints())
+}
+
+pub fn router() -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    masking::validate().expect("Invalid data masking config");
+
+    // POST /api/{v1.0,v2.0}/batch, CORS applied
+    let batch = cors::api_scope().and(
+        requests::routes::batch::post(routes())
+        .with(cors::cors())
+    );
+    let routes = handled(endpoints().or(batch));
+
+    // Idempotency-Key: retried POSTs get the stored response replayed
+    let routes = idempotency::begin()
+        .and(routes)
+        .then(idempotency::store)
+        .recover(idempotency::answer)
+        .unify();
+
+    // Request ID: tag the request span and echo it back in X-Request-Id,
+    // 5xx responses are reported to the error tracker, JSON checked against
+    // the OpenAPI document in debug builds, sensitive fields masked on preview
+    // instances, keys re-cased per API version, bodies compressed per
+    // Accept-Encoding, security headers added, one access log line per request
+    request_id::request_id()
+        .and(context::context())
+        .and(routes)
+        .then(|id: String, context: context::RequestContext, reply| async move {
+            let response = error_report::report(&context, &id, reply);
+            let response = openapi_validation::check_response(&context, response).await;
+            let response = masking::apply(&context, response).await;
+            let response = json_case::apply(&context, response).await;
+            let response = compression::compress(&context, response).await;
+            let response = method::strip_head_body(&context.method, response);
+            let response = security_headers::apply(response);
+            access_log::log(&context, &id, &response);
+            request_id::echo(id, response)
+        })
+        .with(request_id::span())
+}
@@ -1 +1,10 @@
-pub mod test;
\ No newline at end of file
+pub mod test;
+pub mod health;
+pub mod docs;
+pub mod version;
+pub mod method;
+pub mod options;
+pub mod batch;
+pub mod paintings;
+pub mod site;
+pub mod limits;
\ No newline at end of file
@@ -0,0 +1,182 @@
+use warp::{Filter, Rejection, Reply, body, path};
+use warp::http::{HeaderMap, HeaderName, HeaderValue, Method, Request, StatusCode, Uri};
+use warp::http::header::{CONTENT_LENGTH, CONTENT_TYPE};
+use warp::hyper::Body;
+use warp::hyper::service::Service;
+use warp::reply::Response;
+use std::convert::Infallible;
+use std::env;
+use std::net::SocketAddr;
+use tracing::{error, Instrument, Span};
+use crate::requests::dto::batch::{BatchRequest, BatchResponse};
+use crate::requests::middleware::{context, json_case};
+use crate::requests::middleware::context::PeerAddr;
+use crate::requests::routes::test::rejection::ErrorMessage;
+use crate::requests::routes::version::{self, ApiVersion};
+
+// Caller headers every sub-request inherits
+const FORWARDED_HEADERS: [&str; 5] = ["authorization", "x-api-key", "accept-language", "forwarded", "x-forwarded-for"];
+
+fn max_requests() -> usize {
+    env::var("batch_max_requests")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(20)
+}
+
+fn item_error(code: StatusCode, message: &str) -> BatchResponse {
+    BatchResponse {
+        status: code.as_u16(),
+        body: serde_json::json!({ "code": code.as_u16(), "message": message }),
+    }
+}
+
+async fn execute<S>(mut service: S, item: BatchRequest, remote: Option<SocketAddr>, headers: &HeaderMap) -> BatchResponse
+where
+    S: Service<Request<Body>, Response = Response, Error = Infallible> + Send + 'static,
+    S::Future: Send,
+{
+    // Validated before building the request, any of these would fail it
+    let method = match item.method.to_uppercase().parse::<Method>() {
+        Ok(method) => method,
+        Err(_) => return item_error(StatusCode::BAD_REQUEST, "INVALID_METHOD"),
+    };
+    if !item.path.starts_with('/') || item.path.parse::<Uri>().is_err() {
+        return item_error(StatusCode::BAD_REQUEST, "INVALID_PATH");
+    }
+    let path = item.path.split('?').next().unwrap_or_default().to_string();
+    if path.ends_with("/batch") {
+        return item_error(StatusCode::BAD_REQUEST, "NESTED_BATCH");
+    }
+
+    let mut request = Request::builder()
+        .method(method.clone())
+        .uri(&item.path);
+    for name in FORWARDED_HEADERS {
+        if let Some(value) = headers.get(name) {
+            request = request.header(name, value.clone());
+        }
+    }
+    for (name, value) in item.headers.unwrap_or_default() {
+        match (HeaderName::from_bytes(name.as_bytes()), HeaderValue::from_str(&value)) {
+            // Proxy headers come from the caller only, items could spoof their IP
+            (Ok(name), _) if name == "forwarded" || name == "x-forwarded-for" => {
+                return item_error(StatusCode::BAD_REQUEST, "INVALID_HEADER");
+            }
+            (Ok(name), Ok(value)) => request = request.header(name, value),
+            _ => return item_error(StatusCode::BAD_REQUEST, "INVALID_HEADER"),
+        }
+    }
+    let body = match item.body {
+        Some(body) => {
+            let bytes = serde_json::to_vec(&body).unwrap_or_default();
+            request = request
+                .header(CONTENT_TYPE, "application/json")
+                .header(CONTENT_LENGTH, bytes.len());
+            Body::from(bytes)
+        }
+        None => Body::empty(),
+    };
+    let mut request = match request.body(body) {
+        Ok(request) => request,
+        Err(_) => return item_error(StatusCode::BAD_REQUEST, "INVALID_REQUEST"),
+    };
+    if let Some(remote) = remote {
+        request.extensions_mut().insert(PeerAddr(remote));
+    }
+
+    // warp refuses nested filter calls, so each sub-request gets its own task
+    let response = tokio::spawn(
+        async move {
+            let response = service.call(request).await.unwrap_or_else(|never| match never {});
+            let (parts, body) = response.into_parts();
+            warp::hyper::body::to_bytes(body).await.map(|bytes| (parts, bytes))
+        }
+        .instrument(Span::current())
+    ).await;
+    let (parts, bytes) = match response {
+        Ok(Ok(response)) => response,
+        Ok(Err(e)) => {
+            error!("Batch sub-request body failed: {}", e);
+            return item_error(StatusCode::INTERNAL_SERVER_ERROR, "SUB_REQUEST_FAILED");
+        }
+        Err(e) => {
+            error!("Batch sub-request failed: {}", e);
+            return item_error(StatusCode::INTERNAL_SERVER_ERROR, "SUB_REQUEST_FAILED");
+        }
+    };
+    let is_json = parts.headers
+        .get("content-type")
+        .is_some_and(|value| value.as_bytes().starts_with(b"application/json"));
+    // Keyed like the same request made on its own would be
+    let body = if is_json {
+        let body = serde_json::from_slice(&bytes).unwrap_or_default();
+        json_case::convert_body(&method, &path, parts.status, body)
+    } else {
+        serde_json::Value::String(String::from_utf8_lossy(&bytes).to_string())
+    };
+
+    BatchResponse {
+        status: parts.status.as_u16(),
+        body,
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/{version}/batch",
+    params(("version" = String, Path, description = "v1.0 or v2.0")),
+    request_body = Vec<BatchRequest>,
+    responses(
+        (status = 200, description = "Sub-request results in request order", body = Vec<BatchResponse>),
+        (status = 400, description = "Too many sub-requests", body = ErrorMessage),
+    ),
+)]
+async fn post_batch<S>(
+    _version: ApiVersion,
+    service: S,
+    remote: Option<SocketAddr>,
+    headers: HeaderMap,
+    requests: Vec<BatchRequest>,
+) -> Result<Box<dyn Reply>, Rejection>
+where
+    S: Service<Request<Body>, Response = Response, Error = Infallible> + Clone + Send + 'static,
+    S::Future: Send,
+{
+    if requests.len() > max_requests() {
+        let json = warp::reply::json(&ErrorMessage {
+            code: StatusCode::BAD_REQUEST.as_u16(),
+            message: String::from("BATCH_TOO_LARGE"),
+        });
+        return Ok(Box::new(warp::reply::with_status(json, StatusCode::BAD_REQUEST)));
+    }
+
+    // Sequential on purpose, later items may depend on earlier ones
+    let mut results = Vec::with_capacity(requests.len());
+    for item in requests {
+        results.push(execute(service.clone(), item, remote, &headers).await);
+    }
+
+    Ok(Box::new(warp::reply::json(&results)))
+}
+
+// Sub-requests are dispatched to `routes`, built once by the router
+pub fn post<F, R>(routes: F) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone
+where
+    F: Filter<Extract = (R,), Error = Rejection> + Clone + Send + Sync + 'static,
+    F::Future: Send,
+    R: Reply,
+{
+    let service = warp::service(routes);
+
+    warp::post()
+        .and(version::mount(version::ALL))
+        .and(path("batch"))
+        .and(path::end())
+        .and(warp::any().map(move || service.clone()))
+        .and(context::remote())
+        .and(warp::header::headers_cloned())
+        .and(body::content_length_limit(1024 * 1024))
+        .and(body::json())
+        .and_then(post_batch)
+}
@@ -0,0 +1,2 @@
+pub mod openapi_json;
+pub mod swagger_ui;
\ No newline at end of file
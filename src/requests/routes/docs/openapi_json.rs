@@ -0,0 +1,25 @@
+use warp::{Filter, Rejection, Reply, path};
+use utoipa::OpenApi;
+use crate::requests::middleware::json_case;
+use crate::requests::openapi::ApiDoc;
+use crate::requests::routes::{method, version::{self, ApiVersion}};
+
+#[utoipa::path(
+    get,
+    path = "/api/{version}/openapi.json",
+    params(("version" = String, Path, description = "v1.0 or v2.0")),
+    responses((status = 200, description = "This document", content_type = "application/json")),
+)]
+// Same routes for every version, schema properties in the version's key case
+async fn get_openapi_json(version: ApiVersion) -> Result<impl Reply, Rejection> {
+    let document = serde_json::to_value(ApiDoc::openapi()).unwrap_or_default();
+    Ok(warp::reply::json(&json_case::convert_document(document, version.key_case())))
+}
+
+pub fn get() -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    method::get_or_head()
+        .and(version::mount(version::ALL))
+        .and(path("openapi.json"))
+        .and(path::end())
+        .and_then(get_openapi_json)
+}
@@ -0,0 +1,44 @@
+use warp::{Filter, Rejection, Reply, path};
+use warp::http::{Response, StatusCode, Uri};
+use warp::path::{FullPath, Tail};
+use std::sync::Arc;
+use utoipa_swagger_ui::Config;
+use crate::requests::routes::method;
+
+#[utoipa::path(
+    get,
+    path = "/api/docs/{file}",
+    params(("file" = String, Path, description = "Swagger UI asset, index.html when empty")),
+    responses((status = 200, description = "Swagger UI")),
+)]
+async fn get_swagger_ui(full_path: FullPath, tail: Tail, config: Arc<Config<'static>>) -> Result<Box<dyn Reply>, Rejection> {
+    // Relative asset URLs in index.html need the trailing slash
+    if full_path.as_str() == "/api/docs" {
+        return Ok(Box::new(warp::redirect::found(Uri::from_static("/api/docs/"))));
+    }
+
+    match utoipa_swagger_ui::serve(tail.as_str(), config) {
+        Ok(Some(file)) => Ok(Box::new(
+            Response::builder()
+                .header("Content-Type", file.content_type)
+                .body(file.bytes.to_vec())
+        )),
+        Ok(None) => Ok(Box::new(StatusCode::NOT_FOUND)),
+        Err(e) => Ok(Box::new(
+            Response::builder()
+                .status(StatusCode::INTERNAL_SERVER_ERROR)
+                .body(e.to_string())
+        )),
+    }
+}
+
+pub fn get() -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    let config = Arc::new(Config::from("/api/v1.0/openapi.json"));
+
+    method::get_or_head()
+        .and(path!("api" / "docs" / ..))
+        .and(path::full())
+        .and(path::tail())
+        .and(warp::any().map(move || config.clone()))
+        .and_then(get_swagger_ui)
+}
@@ -0,0 +1,3 @@
+pub mod healthz;
+pub mod readyz;
+pub mod livez;
\ No newline at end of file
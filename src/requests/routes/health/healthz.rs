@@ -0,0 +1,19 @@
+use warp::{Filter, Rejection, Reply, path};
+use crate::requests::dto::health::HealthReport;
+use crate::requests::routes::method;
+
+#[utoipa::path(
+    get,
+    path = "/healthz",
+    responses((status = 200, description = "Process is up", body = HealthReport)),
+)]
+async fn get_healthz() -> Result<impl Reply, Rejection> {
+    Ok(warp::reply::json(&HealthReport::new(vec![])))
+}
+
+pub fn get() -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    method::get_or_head()
+        .and(path("healthz"))
+        .and(path::end())
+        .and_then(get_healthz)
+}
@@ -0,0 +1,19 @@
+use warp::{Filter, Rejection, Reply, path};
+use crate::requests::dto::health::HealthReport;
+use crate::requests::routes::method;
+
+#[utoipa::path(
+    get,
+    path = "/livez",
+    responses((status = 200, description = "Process is alive", body = HealthReport)),
+)]
+async fn get_livez() -> Result<impl Reply, Rejection> {
+    Ok(warp::reply::json(&HealthReport::new(vec![])))
+}
+
+pub fn get() -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    method::get_or_head()
+        .and(path("livez"))
+        .and(path::end())
+        .and_then(get_livez)
+}
@@ -0,0 +1,47 @@
+use warp::{Filter, Rejection, Reply, path};
+use warp::http::StatusCode;
+use std::time::{Duration, Instant};
+use tokio::time::timeout;
+use crate::database::connection::get_client;
+use crate::requests::dto::health::{HealthCheck, HealthReport};
+use crate::requests::routes::method;
+
+async fn check_database() -> HealthCheck {
+    let start = Instant::now();
+    let result = match get_client().await {
+        Ok(client) => match timeout(Duration::from_secs(2), client.query_one("SELECT 1", &[])).await {
+            Ok(Ok(_)) => Ok(()),
+            Ok(Err(e)) => Err(e.to_string()),
+            Err(_) => Err(String::from("timed out")),
+        },
+        Err(e) => Err(e.to_string()),
+    };
+
+    HealthCheck {
+        name: String::from("database"),
+        ok: result.is_ok(),
+        latency_ms: start.elapsed().as_millis(),
+        error: result.err(),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/readyz",
+    responses(
+        (status = 200, description = "All dependencies reachable", body = HealthReport),
+        (status = 503, description = "A dependency check failed", body = HealthReport),
+    ),
+)]
+async fn get_readyz() -> Result<impl Reply, Rejection> {
+    let report = HealthReport::new(vec![check_database().await]);
+    let code = if report.is_ok() { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+    Ok(warp::reply::with_status(warp::reply::json(&report), code))
+}
+
+pub fn get() -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    method::get_or_head()
+        .and(path("readyz"))
+        .and(path::end())
+        .and_then(get_readyz)
+}
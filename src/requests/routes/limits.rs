@@ -0,0 +1,37 @@
+use warp::{Filter, Rejection, Reply, path};
+use warp::http::HeaderMap;
+use std::net::SocketAddr;
+use crate::requests::dto::limits::{Limits, Quota};
+use crate::requests::middleware::{context, rate_limit};
+use crate::requests::routes::{method, version::{self, ApiVersion}};
+
+#[utoipa::path(
+    get,
+    path = "/api/{version}/limits",
+    params(("version" = String, Path, description = "v1.0 or v2.0")),
+    responses((status = 200, description = "Remaining quota of the caller, this request included", body = Limits)),
+)]
+async fn get_limits(_version: ApiVersion, remote: Option<SocketAddr>, headers: HeaderMap) -> Result<impl Reply, Rejection> {
+    let quotas = rate_limit::quota(remote, &headers)
+        .into_iter()
+        .map(|(bucket, policy, decision)| Quota {
+            bucket: String::from(bucket),
+            limit: decision.limit,
+            period_secs: policy.period.as_secs(),
+            remaining: decision.remaining,
+            reset: decision.reset,
+        })
+        .collect();
+
+    Ok(warp::reply::json(&Limits { client: String::from("ip"), quotas }))
+}
+
+pub fn get() -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    method::get_or_head()
+        .and(version::mount(version::ALL))
+        .and(path("limits"))
+        .and(path::end())
+        .and(context::remote())
+        .and(warp::header::headers_cloned())
+        .and_then(get_limits)
+}
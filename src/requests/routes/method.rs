@@ -0,0 +1,23 @@
+use warp::{Filter, Rejection};
+use warp::http::Method;
+use warp::http::header::{HeaderValue, CONTENT_LENGTH};
+use warp::hyper::body::{Body, HttpBody};
+use warp::reply::Response;
+
+// GET routes answer HEAD too, the body is dropped on the way out
+pub fn get_or_head() -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    warp::get().or(warp::head()).unify()
+}
+
+// Headers only for HEAD, Content-Length still describes the GET body
+pub fn strip_head_body(method: &Method, response: Response) -> Response {
+    if method != Method::HEAD {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    if let Some(size) = body.size_hint().exact() {
+        parts.headers.insert(CONTENT_LENGTH, HeaderValue::from(size));
+    }
+    Response::from_parts(parts, Body::empty())
+}
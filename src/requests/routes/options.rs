@@ -0,0 +1,99 @@
+use warp::{Filter, Rejection, Reply, path};
+use warp::http::{Method, StatusCode};
+use warp::path::FullPath;
+use warp::reject::Reject;
+use lazy_static::lazy_static;
+use utoipa::OpenApi;
+use crate::requests::openapi::ApiDoc;
+use crate::requests::routes::version::{self, ApiVersion};
+
+lazy_static! {
+    // Path templates and their methods, taken from the OpenAPI document
+    static ref ALLOWED: Vec<(Vec<String>, Vec<&'static str>)> = ApiDoc::openapi()
+        .paths
+        .paths
+        .iter()
+        .map(|(template, item)| {
+            let mut methods = Vec::new();
+            if item.get.is_some() {
+                methods.extend(["GET", "HEAD"]);
+            }
+            if item.post.is_some() {
+                methods.push("POST");
+            }
+            if item.put.is_some() {
+                methods.push("PUT");
+            }
+            if item.patch.is_some() {
+                methods.push("PATCH");
+            }
+            if item.delete.is_some() {
+                methods.push("DELETE");
+            }
+            methods.push("OPTIONS");
+            (template.split('/').map(String::from).collect(), methods)
+        })
+        .collect();
+}
+
+// {version} only matches the versions that are mounted
+pub fn matches(template: &[String], path: &str) -> bool {
+    let segments: Vec<&str> = path.split('/').collect();
+    template.len() == segments.len()
+        && template.iter().zip(segments).all(|(expected, segment)| match expected.as_str() {
+            "{version}" => segment.parse::<ApiVersion>().is_ok_and(|v| version::ALL.contains(&v)),
+            _ if expected.starts_with('{') => !segment.is_empty(),
+            _ => expected == segment,
+        })
+}
+
+// Methods of the documented path, None for an unknown path
+pub fn allowed(path: &str) -> Option<&'static [&'static str]> {
+    ALLOWED
+        .iter()
+        .find(|(template, _)| matches(template, path))
+        .map(|(_, methods)| methods.as_slice())
+}
+
+// No route answered: 405 with the Allow header when the path is documented
+// but not for this method, 404 otherwise
+#[derive(Debug)]
+pub struct Unrouted {
+    pub allow: Option<String>,
+}
+
+impl Reject for Unrouted {}
+
+// CORS preflights are answered by the CORS filter, a rejected one must
+// reach the client as 403 rather than a 204 from here
+async fn options_allow(path: FullPath, preflight: Option<String>) -> Result<impl Reply, Rejection> {
+    if preflight.is_some() {
+        return Err(warp::reject::not_found());
+    }
+
+    let allowed = allowed(path.as_str()).ok_or_else(warp::reject::not_found)?;
+
+    Ok(warp::reply::with_header(StatusCode::NO_CONTENT, "allow", allowed.join(", ")))
+}
+
+pub fn options() -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    warp::options()
+        .and(path::full())
+        .and(warp::header::optional::<String>("access-control-request-method"))
+        .and_then(options_allow)
+}
+
+async fn unrouted(method: Method, path: FullPath) -> Result<StatusCode, Rejection> {
+    let allow = allowed(path.as_str())
+        .filter(|methods| !methods.contains(&method.as_str()))
+        .map(|methods| methods.join(", "));
+
+    Err(warp::reject::custom(Unrouted { allow }))
+}
+
+// Last in line, always rejects so the 404/405 decision sees the path
+pub fn fallback() -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    warp::method()
+        .and(path::full())
+        .and_then(unrouted)
+}
@@ -0,0 +1,2 @@
+pub mod stats;
+pub mod qr;
\ No newline at end of file
@@ -0,0 +1,164 @@
+use warp::{Filter, Rejection, Reply, path, query};
+use warp::http::StatusCode;
+use warp::reply::Response;
+use bytes::Bytes;
+use lazy_static::lazy_static;
+use qrcode::{Color, EcLevel, QrCode};
+use std::collections::HashMap;
+use std::env;
+use std::sync::Mutex;
+use tracing::{error, info_span, Instrument};
+use uuid::Uuid;
+use crate::database::connection::get_client;
+use crate::requests::dto::qr::{Ecc, QrParams};
+use crate::requests::routes::{method, version::{self, ApiVersion}};
+use crate::requests::routes::test::rejection::ErrorMessage;
+
+// Modules of white border the QR spec asks for
+const QUIET_ZONE: u32 = 4;
+// Past this many rendered codes the cache starts over
+const MAX_CACHED: usize = 256;
+
+const EXISTS_QUERY: &str = "SELECT 1 FROM paintings WHERE id = CAST($1::text AS uuid) AND deleted IS NULL";
+
+lazy_static! {
+    static ref CACHE: Mutex<HashMap<(Uuid, u32, Ecc), Bytes>> = Mutex::new(HashMap::new());
+}
+
+// $painting_public_url: public page of a painting, {id} is replaced
+fn public_url(id: &Uuid) -> String {
+    env::var("painting_public_url")
+        .unwrap_or_else(|_| String::from("http://localhost/paintings/{id}"))
+        .replace("{id}", &id.to_string())
+}
+
+fn render(url: &str, size: u32, ecc: Ecc) -> Result<Bytes, String> {
+    let level = match ecc {
+        Ecc::L => EcLevel::L,
+        Ecc::M => EcLevel::M,
+        Ecc::Q => EcLevel::Q,
+        Ecc::H => EcLevel::H,
+    };
+    let code = QrCode::with_error_correction_level(url, level).map_err(|e| e.to_string())?;
+    let width = code.width() as u32;
+    let colors = code.to_colors();
+
+    let modules = width + 2 * QUIET_ZONE;
+    let scale = (size / modules).max(1);
+    let pixels_per_side = modules * scale;
+
+    // 8-bit grayscale, one byte per pixel
+    let mut pixels = vec![255u8; (pixels_per_side * pixels_per_side) as usize];
+    for (index, color) in colors.iter().enumerate() {
+        if *color != Color::Dark {
+            continue;
+        }
+        let x = (index as u32 % width + QUIET_ZONE) * scale;
+        let y = (index as u32 / width + QUIET_ZONE) * scale;
+        for row in y..y + scale {
+            let start = (row * pixels_per_side + x) as usize;
+            pixels[start..start + scale as usize].fill(0);
+        }
+    }
+
+    let mut png = Vec::new();
+    let mut encoder = png::Encoder::new(&mut png, pixels_per_side, pixels_per_side);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().map_err(|e| e.to_string())?;
+    writer.write_image_data(&pixels).map_err(|e| e.to_string())?;
+    writer.finish().map_err(|e| e.to_string())?;
+
+    Ok(Bytes::from(png))
+}
+
+async fn painting_exists(id: &Uuid) -> Result<bool, String> {
+    let client = get_client().await.map_err(|e| e.to_string())?;
+    client
+        .query_opt(EXISTS_QUERY, &[&id.to_string()])
+        .instrument(info_span!("db.query", db.system = "postgresql", db.statement = EXISTS_QUERY))
+        .await
+        .map(|row| row.is_some())
+        .map_err(|e| e.to_string())
+}
+
+fn png_reply(png: Bytes) -> Response {
+    let reply = warp::reply::with_header(png.to_vec(), "content-type", "image/png");
+    warp::reply::with_header(reply, "cache-control", "public, max-age=86400").into_response()
+}
+
+fn error_reply(code: StatusCode, message: &str) -> Response {
+    let json = warp::reply::json(&ErrorMessage {
+        code: code.as_u16(),
+        message: String::from(message),
+    });
+    warp::reply::with_status(json, code).into_response()
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/{version}/paintings/{id}/qr.png",
+    params(
+        ("version" = String, Path, description = "v1.0 or v2.0"),
+        ("id" = Uuid, Path, description = "Painting id"),
+        QrParams,
+    ),
+    responses(
+        (status = 200, description = "QR code linking to the painting's public page", content_type = "image/png"),
+        (status = 404, description = "No such painting", body = ErrorMessage),
+        (status = 503, description = "Database unavailable", body = ErrorMessage),
+    ),
+)]
+async fn get_painting_qr(_version: ApiVersion, id: Uuid, params: QrParams) -> Result<impl Reply, Rejection> {
+    let size = params.size.unwrap_or(300).clamp(64, 2048);
+    let ecc = params.ecc.unwrap_or(Ecc::M);
+    let key = (id, size, ecc);
+
+    // Existence first, the cache only saves rendering. A painting deleted
+    // after its code was cached 404s like any other.
+    match painting_exists(&id).await {
+        Ok(true) => {}
+        Ok(false) => return Ok(error_reply(StatusCode::NOT_FOUND, "NOT_FOUND")),
+        Err(e) => {
+            error!("Painting lookup failed: {}", e);
+            return Ok(error_reply(StatusCode::SERVICE_UNAVAILABLE, "SERVICE_UNAVAILABLE"));
+        }
+    }
+
+    if let Some(png) = CACHE.lock().unwrap().get(&key).cloned() {
+        return Ok(png_reply(png));
+    }
+
+    // Up to 2048x2048 pixels, kept off the async workers
+    let url = public_url(&id);
+    let rendered = tokio::task::spawn_blocking(move || render(&url, size, ecc))
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|result| result);
+    let png = match rendered {
+        Ok(png) => png,
+        Err(e) => {
+            error!("QR rendering failed: {}", e);
+            return Ok(error_reply(StatusCode::INTERNAL_SERVER_ERROR, "QR_RENDER_FAILED"));
+        }
+    };
+
+    let mut cache = CACHE.lock().unwrap();
+    if cache.len() >= MAX_CACHED {
+        cache.clear();
+    }
+    cache.insert(key, png.clone());
+
+    Ok(png_reply(png))
+}
+
+pub fn get() -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    method::get_or_head()
+        .and(version::mount(version::ALL))
+        .and(path("paintings"))
+        .and(path::param::<Uuid>())
+        .and(path("qr.png"))
+        .and(path::end())
+        .and(query::<QrParams>())
+        .and_then(get_painting_qr)
+}
@@ -0,0 +1,62 @@
+use warp::{Filter, Rejection, Reply, path};
+use warp::http::StatusCode;
+use tokio_postgres::Row;
+use tracing::{error, info_span, Instrument};
+use crate::database::connection::get_client;
+use crate::requests::dto::painting_stats::{PaintingStats, PriceStats, Range};
+use crate::requests::routes::{method, version::{self, ApiVersion}};
+use crate::requests::routes::test::rejection::ErrorMessage;
+
+const STATS_QUERY: &str = "SELECT count(*), min(price), max(price), avg(price)::float8, \
+    min(width), max(width), min(height), max(height) \
+    FROM paintings WHERE deleted IS NULL";
+
+fn from_row(row: &Row) -> PaintingStats {
+    PaintingStats {
+        count: row.get(0),
+        price: PriceStats { min: row.get(1), max: row.get(2), average: row.get(3) },
+        width: Range { min: row.get(4), max: row.get(5) },
+        height: Range { min: row.get(6), max: row.get(7) },
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/{version}/paintings/stats",
+    params(("version" = String, Path, description = "v1.0 or v2.0")),
+    responses(
+        (status = 200, description = "Price and dimension bounds of the catalogue", body = PaintingStats),
+        (status = 503, description = "Database unavailable", body = ErrorMessage),
+    ),
+)]
+async fn get_painting_stats(_version: ApiVersion) -> Result<impl Reply, Rejection> {
+    let result = match get_client().await {
+        Ok(client) => client
+            .query_one(STATS_QUERY, &[])
+            .instrument(info_span!("db.query", db.system = "postgresql", db.statement = STATS_QUERY))
+            .await
+            .map_err(|e| e.to_string()),
+        Err(e) => Err(e.to_string()),
+    };
+
+    Ok(match result {
+        Ok(row) => warp::reply::with_status(warp::reply::json(&from_row(&row)), StatusCode::OK),
+        Err(e) => {
+            error!("Painting stats query failed: {}", e);
+            let body = ErrorMessage {
+                code: StatusCode::SERVICE_UNAVAILABLE.as_u16(),
+                message: String::from("SERVICE_UNAVAILABLE"),
+            };
+            warp::reply::with_status(warp::reply::json(&body), StatusCode::SERVICE_UNAVAILABLE)
+        }
+    })
+}
+
+pub fn get() -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    method::get_or_head()
+        .and(version::mount(version::ALL))
+        .and(path("paintings"))
+        .and(path("stats"))
+        .and(path::end())
+        .and_then(get_painting_stats)
+}
@@ -0,0 +1,2 @@
+pub mod robots;
+pub mod security_txt;
\ No newline at end of file
@@ -0,0 +1,37 @@
+use warp::{Filter, Rejection, Reply, path};
+use std::env;
+use crate::requests::routes::method;
+use crate::utils::environment::{self, Environment};
+
+// Only production is indexable, staging and development disallow everything
+fn robots_txt() -> String {
+    if environment::current() != Environment::Production {
+        return String::from("User-agent: *\nDisallow: /\n");
+    }
+
+    let disallow = environment::list("robots_disallow").unwrap_or_else(|| vec![String::from("/api/")]);
+    let mut body = String::from("User-agent: *\n");
+    for path in disallow {
+        body.push_str(&format!("Disallow: {}\n", path));
+    }
+    if let Ok(sitemap) = env::var("robots_sitemap") {
+        body.push_str(&format!("\nSitemap: {}\n", sitemap));
+    }
+    body
+}
+
+#[utoipa::path(
+    get,
+    path = "/robots.txt",
+    responses((status = 200, description = "Crawler rules", body = String, content_type = "text/plain")),
+)]
+async fn get_robots_txt() -> Result<impl Reply, Rejection> {
+    Ok(warp::reply::with_header(robots_txt(), "content-type", "text/plain; charset=utf-8"))
+}
+
+pub fn get() -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    method::get_or_head()
+        .and(path("robots.txt"))
+        .and(path::end())
+        .and_then(get_robots_txt)
+}
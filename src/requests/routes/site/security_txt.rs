@@ -0,0 +1,98 @@
+use warp::{Filter, Rejection, Reply, path};
+use warp::http::StatusCode;
+use warp::reply::Response;
+use chrono::{DateTime, SecondsFormat, Utc};
+use std::env;
+use tracing::warn;
+use crate::requests::routes::method;
+use crate::requests::routes::test::rejection::ErrorMessage;
+use crate::utils::environment;
+
+// Warn this long before Expires passes
+const RENEW_DAYS: i64 = 30;
+
+// $security_expires (RFC 3339) has no default, RFC 9116 wants somebody to
+// review the file and bump the date by hand
+pub fn expires() -> Result<DateTime<Utc>, String> {
+    let value = env::var("security_expires").map_err(|_| String::from("$security_expires is not set"))?;
+    DateTime::parse_from_rfc3339(&value)
+        .map(|expires| expires.with_timezone(&Utc))
+        .map_err(|e| format!("$security_expires {:?}: {}", value, e))
+}
+
+// Days left, an error once it is due for review
+pub fn check_expires() -> Result<i64, String> {
+    let days = (expires()? - Utc::now()).num_days();
+    if days < RENEW_DAYS {
+        return Err(format!("security.txt expires in {} days, review it and bump $security_expires", days));
+    }
+    Ok(days)
+}
+
+// RFC 9116, Contact and Expires are required. Without $security_contact or a
+// valid $security_expires, or once Expires has passed, the file isn't served
+// at all.
+fn security_txt() -> Option<String> {
+    let contacts = environment::list("security_contact").filter(|contacts| !contacts.is_empty())?;
+    let expires = match expires() {
+        Ok(expires) => expires,
+        Err(e) => {
+            warn!("security.txt not served: {}", e);
+            return None;
+        }
+    };
+    if expires <= Utc::now() {
+        warn!("security.txt not served: expired {}, bump $security_expires", expires);
+        return None;
+    }
+    if let Err(e) = check_expires() {
+        warn!("{}", e);
+    }
+    let expires = expires.to_rfc3339_opts(SecondsFormat::Secs, true);
+
+    let mut body = String::new();
+    for contact in contacts {
+        body.push_str(&format!("Contact: {}\n", contact));
+    }
+    body.push_str(&format!("Expires: {}\n", expires));
+    body.push_str(&format!(
+        "Preferred-Languages: {}\n",
+        env::var("security_languages").unwrap_or_else(|_| String::from("cs, en"))
+    ));
+    if let Ok(policy) = env::var("security_policy") {
+        body.push_str(&format!("Policy: {}\n", policy));
+    }
+    if let Ok(canonical) = env::var("security_canonical") {
+        body.push_str(&format!("Canonical: {}\n", canonical));
+    }
+    Some(body)
+}
+
+#[utoipa::path(
+    get,
+    path = "/.well-known/security.txt",
+    responses(
+        (status = 200, description = "Security contact information", body = String, content_type = "text/plain"),
+        (status = 404, description = "No security contact configured, or Expires has passed", body = ErrorMessage),
+    ),
+)]
+async fn get_security_txt() -> Result<Response, Rejection> {
+    match security_txt() {
+        Some(body) => Ok(warp::reply::with_header(body, "content-type", "text/plain; charset=utf-8").into_response()),
+        None => {
+            let json = warp::reply::json(&ErrorMessage {
+                code: StatusCode::NOT_FOUND.as_u16(),
+                message: String::from("NOT_FOUND"),
+            });
+            Ok(warp::reply::with_status(json, StatusCode::NOT_FOUND).into_response())
+        }
+    }
+}
+
+pub fn get() -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    method::get_or_head()
+        .and(path(".well-known"))
+        .and(path("security.txt"))
+        .and(path::end())
+        .and_then(get_security_txt)
+}
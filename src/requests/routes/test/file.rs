@@ -2,10 +2,18 @@ use warp::Filter;
 use warp::Buf;
 use bytes::BufMut;
 use futures_util::TryStreamExt;
+use tracing::{debug, instrument};
 // use warp::http::StatusCode;
 // use warp::multipart::{FormData, Part};
 // use sha2::{Sha256, Digest};
 
+#[utoipa::path(
+    post,
+    path = "/file",
+    request_body(content_type = "multipart/form-data", description = "Files to echo back"),
+    responses((status = 200, description = "Field names, file names and contents", body = String)),
+)]
+#[instrument(skip_all)]
 async fn post_file(form: warp::multipart::FormData) -> Result<impl warp::Reply, warp::Rejection> {
     let field_names: Vec<_> = form
         .and_then(|mut field| async move {
@@ -14,13 +22,13 @@ async fn post_file(form: warp::multipart::FormData) -> Result<impl warp::Reply,
             // field.data() only returns a piece of the content, you should call over it until it replies None
             while let Some(content) = field.data().await {
                 let content = content.unwrap();
-                println!("Content: {:?}", std::str::from_utf8(content.chunk()));
+                debug!("Content: {:?}", std::str::from_utf8(content.chunk()));
                 bytes.put(content);
             }
             Ok((
                 field.name().to_string(),
                 field.filename().unwrap().to_string(),
-                String::from_utf8_lossy(&*bytes).to_string(),
+                String::from_utf8_lossy(&bytes).to_string(),
             ))
         })
         .try_collect()
@@ -1,6 +1,15 @@
 use warp::{Filter, Rejection, Reply, body, path};
 use crate::requests::dto::employee::Employee;
+use tracing::instrument;
 
+#[utoipa::path(
+    post,
+    path = "/promote/{rate}",
+    params(("rate" = u32, Path, description = "New rate")),
+    request_body = Employee,
+    responses((status = 200, description = "Promoted employee", body = Employee)),
+)]
+#[instrument]
 async fn post_promote(rate: u32, employee: Employee) -> Result<impl Reply, Rejection> {
     let promoted = Employee {
         name: employee.name,
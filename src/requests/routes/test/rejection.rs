@@ -1,35 +1,64 @@
 use std::error::Error;
+use warp::Reply;
 use warp::http::StatusCode;
+use warp::http::header::{HeaderValue, ALLOW, RETRY_AFTER};
+use tracing::error;
+use crate::requests::middleware::maintenance::{self, UnderMaintenance};
+use crate::requests::middleware::rate_limit::{self, RateLimited};
+use crate::requests::routes::options::Unrouted;
 
-#[allow(dead_code)]
-#[derive(serde_derive::Serialize)]
-struct ErrorMessage {
-    code: u16,
-    message: String,
+#[derive(serde_derive::Serialize, utoipa::ToSchema)]
+pub struct ErrorMessage {
+    pub code: u16,
+    pub message: String,
 }
 
-#[allow(dead_code)]
 pub async fn handle_rejection(err: warp::Rejection) -> Result<impl warp::Reply, warp::Rejection> {
+    if err.find::<UnderMaintenance>().is_some() {
+        return Ok(maintenance::response());
+    }
+
     let code;
     let message;
-    eprintln!("Request Error: {:?}", err);
+    let mut allow = None;
+    error!("Request Error: {:?}", err);
 
-    if err.is_not_found() {
-        code = StatusCode::NOT_FOUND;
-        message = "NOT_FOUND";
+    // Errors of a route whose path matched come before the 404/405 decision,
+    // every rejection from endpoints() also carries an Unrouted
+    if err.find::<RateLimited>().is_some() {
+        code = StatusCode::TOO_MANY_REQUESTS;
+        message = "TOO_MANY_REQUESTS";
+    } else if err.find::<warp::cors::CorsForbidden>().is_some() {
+        code = StatusCode::FORBIDDEN;
+        message = "CORS_FORBIDDEN";
     } else if let Some(e) = err.find::<warp::filters::body::BodyDeserializeError>() {
         message = match e.source() {
-            Some(cause) => {
-                if cause.to_string().contains("denom") {
-                    "FIELD_ERROR: denom"
-                } else {
-                    "BAD_REQUEST"
-                }
-            }
-            None => "BAD_REQUEST",
+            Some(cause) if cause.to_string().contains("denom") => "FIELD_ERROR: denom",
+            _ => "BAD_REQUEST",
         };
         code = StatusCode::BAD_REQUEST;
-    } else if let Some(_) = err.find::<warp::reject::MethodNotAllowed>() {
+    } else if err.find::<warp::reject::LengthRequired>().is_some() {
+        code = StatusCode::LENGTH_REQUIRED;
+        message = "LENGTH_REQUIRED";
+    } else if err.find::<warp::reject::PayloadTooLarge>().is_some() {
+        code = StatusCode::PAYLOAD_TOO_LARGE;
+        message = "PAYLOAD_TOO_LARGE";
+    } else if err.find::<warp::reject::UnsupportedMediaType>().is_some() {
+        code = StatusCode::UNSUPPORTED_MEDIA_TYPE;
+        message = "UNSUPPORTED_MEDIA_TYPE";
+    } else if err.find::<warp::reject::InvalidQuery>().is_some()
+        || err.find::<warp::reject::InvalidHeader>().is_some()
+        || err.find::<warp::reject::MissingHeader>().is_some() {
+        code = StatusCode::BAD_REQUEST;
+        message = "BAD_REQUEST";
+    } else if let Some(Unrouted { allow: Some(methods) }) = err.find::<Unrouted>() {
+        code = StatusCode::METHOD_NOT_ALLOWED;
+        message = "METHOD_NOT_ALLOWED";
+        allow = Some(methods.clone());
+    } else if err.is_not_found() || err.find::<Unrouted>().is_some() {
+        code = StatusCode::NOT_FOUND;
+        message = "NOT_FOUND";
+    } else if err.find::<warp::reject::MethodNotAllowed>().is_some() {
         code = StatusCode::METHOD_NOT_ALLOWED;
         message = "METHOD_NOT_ALLOWED";
     } else {
@@ -42,5 +71,14 @@ pub async fn handle_rejection(err: warp::Rejection) -> Result<impl warp::Reply,
         message: message.into(),
     });
 
-    Ok(warp::reply::with_status(json, code))
+    let mut response = warp::reply::with_status(json, code).into_response();
+    if let Some(limited) = err.find::<RateLimited>() {
+        rate_limit::apply_headers(response.headers_mut(), &limited.decision);
+        response.headers_mut().insert(RETRY_AFTER, HeaderValue::from(limited.decision.retry_after));
+    }
+    if let Some(methods) = allow.and_then(|methods| HeaderValue::from_str(&methods).ok()) {
+        response.headers_mut().insert(ALLOW, methods);
+    }
+
+    Ok(response)
 }
\ No newline at end of file
@@ -1,22 +1,31 @@
 use warp::{Filter, Rejection, Reply, path, query};
 use warp::http::{ Response, StatusCode };
 use crate::requests::dto::salute_you::SaluteYou;
+use crate::requests::routes::method;
 use memory_stats::memory_stats;
+use tracing::{debug, instrument};
 
+#[utoipa::path(
+    get,
+    path = "/salute",
+    params(SaluteYou),
+    responses((status = 200, description = "Greeting", body = String)),
+)]
+#[instrument]
 async fn get_salute(person: SaluteYou) -> Result<impl Reply, Rejection> {
     if let Some(usage) = memory_stats() {
-        println!("Current physical memory usage: {}", usage.physical_mem);
-        println!("Current virtual memory usage: {}", usage.virtual_mem);
+        debug!("Current physical memory usage: {}", usage.physical_mem);
+        debug!("Current virtual memory usage: {}", usage.virtual_mem);
     }
     Ok(
         Response::builder()
             .status(StatusCode::OK)
-            .body(String::from(format!("Hello {} {}", person.first_name, person.last_name)))
+            .body(format!("Hello {} {}", person.first_name, person.last_name))
     )
 }
 
 pub fn get() -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
-    warp::get()
+    method::get_or_head()
         .and(path("salute"))
         .and(query::<SaluteYou>())
         .and_then(get_salute)
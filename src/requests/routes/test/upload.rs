@@ -6,7 +6,15 @@ use warp::http::{ Response, StatusCode };
 use sha2::{ Sha256, Digest };
 use std::sync::{Arc, Mutex};
 use std;
-
+use tracing::instrument;
+
+#[utoipa::path(
+    post,
+    path = "/upload",
+    request_body(content_type = "multipart/form-data", description = "Files to hash, up to 20 MB"),
+    responses((status = 200, description = "Hash and size per field", body = String)),
+)]
+#[instrument(skip_all)]
 async fn post_upload(form: FormData) -> Result<impl Reply, Rejection> {
     let result: Vec<_> = form
         .and_then(| mut field | async move {
@@ -75,7 +83,7 @@ async fn post_upload(form: FormData) -> Result<impl Reply, Rejection> {
             Ok((
                 format!("Content-Type: {}", content_type),
                 format!("hash: {}", hash),
-                format!("size: {} MB", (&final_size / 1024 / 1024).to_string()),
+                format!("size: {} MB", final_size / 1024 / 1024),
                 format!("task count: {}", task_count)
             ))
         })
@@ -86,13 +94,14 @@ async fn post_upload(form: FormData) -> Result<impl Reply, Rejection> {
     Ok::<_, warp::Rejection>(
         Response::builder()
             .status(StatusCode::OK)
-            .body(String::from(format!("{:?}", result)))
+            .body(format!("{:?}", result))
     )
 }
 
 pub fn post() -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
-    warp::multipart::form()
+    warp::post()
         .and(warp::path("upload"))
+        .and(warp::multipart::form())
         .and(warp::body::content_length_limit(1024 * 1024 * 20))
         .and_then(post_upload)
 }
\ No newline at end of file
@@ -0,0 +1,76 @@
+use warp::{Filter, Rejection};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ApiVersion {
+    V1_0,
+    V2_0,
+}
+
+// Key case of JSON responses
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyCase {
+    Snake,
+    Camel,
+}
+
+impl ApiVersion {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiVersion::V1_0 => "v1.0",
+            ApiVersion::V2_0 => "v2.0",
+        }
+    }
+
+    // v1.0 keeps the struct field names, v2.0 is what the JS frontend expects
+    pub fn key_case(&self) -> KeyCase {
+        match self {
+            ApiVersion::V1_0 => KeyCase::Snake,
+            ApiVersion::V2_0 => KeyCase::Camel,
+        }
+    }
+}
+
+impl fmt::Display for ApiVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ApiVersion {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<ApiVersion, ()> {
+        match s {
+            "v1.0" => Ok(ApiVersion::V1_0),
+            "v2.0" => Ok(ApiVersion::V2_0),
+            _ => Err(()),
+        }
+    }
+}
+
+// Matches /api/<version>/.. for any of the given versions and extracts the
+// one requested, so a single handler is mounted under every version it
+// serves and can branch on ApiVersion where a response shape differs
+pub fn mount(versions: &'static [ApiVersion]) -> impl Filter<Extract = (ApiVersion,), Error = Rejection> + Clone {
+    warp::path("api")
+        .and(warp::path::param::<String>())
+        .and_then(move |segment: String| async move {
+            match segment.parse::<ApiVersion>() {
+                Ok(version) if versions.contains(&version) => Ok(version),
+                _ => Err(warp::reject::not_found()),
+            }
+        })
+}
+
+// Version of an /api/<version>/.. path
+pub fn from_path(path: &str) -> Option<ApiVersion> {
+    let mut segments = path.trim_start_matches('/').split('/');
+    if segments.next() != Some("api") {
+        return None;
+    }
+    segments.next()?.parse().ok()
+}
+
+pub const ALL: &[ApiVersion] = &[ApiVersion::V1_0, ApiVersion::V2_0];
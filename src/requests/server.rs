@@ -0,0 +1,210 @@
+use std::any::Any;
+use std::convert::Infallible;
+use std::env;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::panic::AssertUnwindSafe;
+use std::io::ErrorKind;
+use std::os::unix::fs::FileTypeExt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use bytes::Bytes;
+use futures_util::FutureExt;
+use futures_util::future::{try_join_all, BoxFuture};
+use futures_util::stream;
+use sentry::{Hub, SentryFutureExt};
+use tokio::net::{UnixListener, UnixStream};
+use warp::Reply;
+use warp::http::StatusCode;
+use warp::hyper::{body, Body, Request, Server};
+use warp::hyper::body::HttpBody;
+use warp::hyper::server::accept;
+use warp::hyper::server::conn::AddrStream;
+use warp::hyper::service::{make_service_fn, service_fn, Service};
+use warp::reply::Response;
+use tracing::{error, info, warn};
+use crate::requests::middleware::access_log;
+use crate::requests::middleware::context::PeerAddr;
+use crate::requests::middleware::openapi_validation;
+use crate::requests::middleware::request_id;
+use crate::requests::router;
+use crate::requests::routes::test::rejection::ErrorMessage;
+
+// Unix socket peers are local processes, seen as loopback so a proxy on
+// the socket can be trusted like one on 127.0.0.1
+const UNIX_PEER: SocketAddr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 0);
+
+// Larger or chunked request bodies skip OpenAPI validation
+const MAX_VALIDATED_BODY: u64 = 1024 * 1024;
+
+#[derive(Debug, Clone)]
+pub enum Listener {
+    Tcp(SocketAddr),
+    Unix(PathBuf),
+}
+
+impl FromStr for Listener {
+    type Err = String;
+
+    // 127.0.0.1:3030, [::1]:3030 or unix:/run/rest_api.sock
+    fn from_str(s: &str) -> Result<Listener, String> {
+        match s.strip_prefix("unix:") {
+            Some(path) if !path.is_empty() => Ok(Listener::Unix(PathBuf::from(path))),
+            Some(_) => Err(String::from("empty unix socket path")),
+            None => s.parse().map(Listener::Tcp).map_err(|e| format!("{}: {}", s, e)),
+        }
+    }
+}
+
+// $listen: comma separated listeners, all serving the same routes
+pub fn listen_config() -> Result<Vec<Listener>, String> {
+    let value = env::var("listen").unwrap_or_else(|_| String::from("127.0.0.1:3030"));
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::parse)
+        .collect()
+}
+
+pub fn listeners() -> Vec<Listener> {
+    listen_config().expect("Invalid $listen entry")
+}
+
+fn panic_message(panic: &(dyn Any + Send)) -> &str {
+    panic
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| panic.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown panic")
+}
+
+fn internal_error(id: String) -> Response {
+    let json = warp::reply::json(&ErrorMessage {
+        code: StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
+        message: String::from("INTERNAL_SERVER_ERROR"),
+    });
+    request_id::echo(id, warp::reply::with_status(json, StatusCode::INTERNAL_SERVER_ERROR)).into_response()
+}
+
+// A panicking handler answers 500 instead of dropping the connection.
+// The backtrace and Sentry event come from the panic hook.
+async fn respond<S>(mut service: S, mut request: Request<Body>, remote: Option<SocketAddr>) -> Result<Response, Infallible>
+where
+    S: Service<Request<Body>, Response = Response, Error = Infallible>,
+{
+    let id = request_id::ensure(request.headers_mut());
+    if let Some(remote) = remote {
+        request.extensions_mut().insert(PeerAddr(remote));
+    }
+
+    // Debug builds: JSON bodies of a known size up to MAX_VALIDATED_BODY are
+    // checked against the OpenAPI document, the routes enforce their own limits
+    let validated = request.body().size_hint().exact().is_some_and(|size| size <= MAX_VALIDATED_BODY);
+    if validated && openapi_validation::enabled() && openapi_validation::is_json(request.headers()) {
+        let (parts, body) = request.into_parts();
+        let body = match body::to_bytes(body).await {
+            Ok(bytes) => {
+                openapi_validation::check_request(&parts.method, parts.uri.path(), &bytes);
+                Body::from(bytes)
+            }
+            // The handler sees the same failure it would have without validation
+            Err(e) => Body::wrap_stream(stream::once(async move { Err::<Bytes, _>(e) })),
+        };
+        request = Request::from_parts(parts, body);
+    }
+
+    // Request scope for everything reported while handling it, panics
+    // captured by Sentry's panic integration included
+    let hub = Arc::new(Hub::new_from_top(Hub::current()));
+    hub.configure_scope(|scope| {
+        scope.set_tag("method", request.method().as_str());
+        scope.set_tag("route", access_log::path_template(request.uri().path()));
+        scope.set_tag("request_id", &id);
+    });
+
+    match AssertUnwindSafe(service.call(request)).catch_unwind().bind_hub(hub).await {
+        Ok(response) => response,
+        Err(panic) => {
+            error!(request_id = %id, "Handler panicked: {}", panic_message(&*panic));
+            Ok(internal_error(id))
+        }
+    }
+}
+
+// A socket left behind by a previous run blocks the bind, anything else at
+// the path is left alone
+fn remove_stale_socket(path: &Path) -> Result<(), String> {
+    match std::fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.file_type().is_socket() => std::fs::remove_file(path).map_err(|e| e.to_string()),
+        Ok(_) => Err(String::from("path exists and is not a socket")),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+// Binds the listener, the returned future serves it until the server fails
+fn bind<S>(listener: &Listener, service: S) -> Result<BoxFuture<'static, Result<(), String>>, String>
+where
+    S: Service<Request<Body>, Response = Response, Error = Infallible> + Clone + Send + 'static,
+    S::Future: Send,
+{
+    match listener {
+        Listener::Tcp(addr) => {
+            let make_service = make_service_fn(move |conn: &AddrStream| {
+                let remote = conn.remote_addr();
+                let service = service.clone();
+                async move {
+                    Ok::<_, Infallible>(service_fn(move |request| respond(service.clone(), request, Some(remote))))
+                }
+            });
+            let server = Server::try_bind(addr).map_err(|e| e.to_string())?.serve(make_service);
+            info!("Listening on {}", addr);
+            Ok(server.map(|result| result.map_err(|e| e.to_string())).boxed())
+        }
+        Listener::Unix(path) => {
+            remove_stale_socket(path)?;
+            let unix = UnixListener::bind(path).map_err(|e| e.to_string())?;
+            // An accept error (EMFILE and the like) only costs that connection
+            let incoming = stream::unfold(unix, |unix| async move {
+                loop {
+                    match unix.accept().await {
+                        Ok((conn, _)) => return Some((Ok::<_, std::io::Error>(conn), unix)),
+                        Err(e) => {
+                            warn!("Unix socket accept failed: {}", e);
+                            tokio::time::sleep(Duration::from_millis(100)).await;
+                        }
+                    }
+                }
+            });
+            let make_service = make_service_fn(move |_: &UnixStream| {
+                let service = service.clone();
+                async move {
+                    Ok::<_, Infallible>(service_fn(move |request| respond(service.clone(), request, Some(UNIX_PEER))))
+                }
+            });
+            let server = Server::builder(accept::from_stream(incoming)).serve(make_service);
+            info!("Listening on unix:{}", path.display());
+            Ok(server.map(|result| result.map_err(|e| e.to_string())).boxed())
+        }
+    }
+}
+
+// Every listener is bound before any serves, so a bad one fails the start.
+// Returns only when a server stopped with an error.
+pub async fn serve(listeners: Vec<Listener>) -> Result<(), String> {
+    let service = warp::service(router::router());
+
+    let servers = listeners
+        .iter()
+        .map(|listener| {
+            bind(listener, service.clone())
+                .map(|server| server.map(move |result| result.map_err(|e| format!("{:?}: {}", listener, e))))
+                .map_err(|e| format!("Failed to bind {:?}: {}", listener, e))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // The first listener to fail ends serve(), the others are dropped with it
+    try_join_all(servers).await.map(|_| ())
+}
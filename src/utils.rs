@@ -1 +1,7 @@
-pub mod file_system;
\ No newline at end of file
+pub mod file_system;
+pub mod logger;
+pub mod telemetry;
+pub mod error_reporting;
+pub mod environment;
+pub mod rate_limiter;
+pub mod idempotency_store;
\ No newline at end of file
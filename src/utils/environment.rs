@@ -0,0 +1,30 @@
+use std::env;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Environment {
+    Development,
+    Staging,
+    Production,
+}
+
+// $app_env selects per-environment defaults. Unset or unknown values get
+// production's strict defaults, relaxed ones have to be asked for.
+pub fn current() -> Environment {
+    match env::var("app_env").unwrap_or_default().to_lowercase().as_str() {
+        "development" | "dev" => Environment::Development,
+        "staging" => Environment::Staging,
+        _ => Environment::Production,
+    }
+}
+
+// Comma separated env value, empty entries dropped
+pub fn list(name: &str) -> Option<Vec<String>> {
+    let value = env::var(name).ok()?;
+    Some(
+        value
+            .split(',')
+            .map(|item| item.trim().to_string())
+            .filter(|item| !item.is_empty())
+            .collect()
+    )
+}
@@ -0,0 +1,28 @@
+use std::backtrace::Backtrace;
+use std::env;
+use std::panic;
+use sentry::ClientInitGuard;
+use tracing::error;
+
+// Panics go to the log with a backtrace instead of plain stderr
+fn install_panic_hook() {
+    panic::set_hook(Box::new(|info| {
+        error!(backtrace = %Backtrace::force_capture(), "Panic: {}", info);
+    }));
+}
+
+// Sentry reporting, enabled only when $sentry_dsn is set. Keep the guard
+// alive for the lifetime of the process so queued events get flushed.
+pub fn init_error_reporting() -> Option<ClientInitGuard> {
+    // Before sentry::init, Sentry's panic hook calls the one it replaces
+    install_panic_hook();
+
+    let dsn = env::var("sentry_dsn").ok()?;
+    let environment = env::var("sentry_environment").ok();
+
+    Some(sentry::init((dsn, sentry::ClientOptions {
+        release: sentry::release_name!(),
+        environment: environment.map(Into::into),
+        ..Default::default()
+    })))
+}
@@ -0,0 +1,71 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use bytes::Bytes;
+use warp::http::{HeaderMap, StatusCode};
+
+#[derive(Debug, Clone)]
+pub struct StoredResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Bytes,
+}
+
+pub enum Begin {
+    // The caller holds the key now and has to complete or release it
+    Reserved,
+    InFlight,
+    Completed(StoredResponse),
+}
+
+enum Entry {
+    InFlight,
+    Completed(StoredResponse),
+}
+
+// In-memory responses per idempotency key, kept for `window`
+pub struct IdempotencyStore {
+    window: Duration,
+    entries: Mutex<HashMap<String, (Entry, Instant)>>,
+}
+
+impl IdempotencyStore {
+    pub fn new(window: Duration) -> IdempotencyStore {
+        IdempotencyStore {
+            window,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // Lookup and reservation under one lock, two racing requests can't both
+    // end up reserving the key
+    pub fn begin(&self, key: &str) -> Begin {
+        let now = Instant::now();
+        let mut entries = self.entries.lock().unwrap();
+        match entries.get(key) {
+            Some((Entry::Completed(response), expires)) if *expires > now => Begin::Completed(response.clone()),
+            Some((Entry::InFlight, expires)) if *expires > now => Begin::InFlight,
+            _ => {
+                entries.insert(key.to_string(), (Entry::InFlight, now + self.window));
+                Begin::Reserved
+            }
+        }
+    }
+
+    pub fn complete(&self, key: &str, response: StoredResponse) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.insert(key.to_string(), (Entry::Completed(response), Instant::now() + self.window));
+    }
+
+    pub fn release(&self, key: &str) {
+        self.entries.lock().unwrap().remove(key);
+    }
+
+    // Drops expired keys, returns how many were removed
+    pub fn purge(&self) -> usize {
+        let mut entries = self.entries.lock().unwrap();
+        let before = entries.len();
+        entries.retain(|_, (_, expires)| *expires > Instant::now());
+        before - entries.len()
+    }
+}
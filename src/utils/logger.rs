@@ -0,0 +1,36 @@
+use dotenv::dotenv;
+use std::env;
+use tracing_subscriber::{EnvFilter, Layer};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use crate::utils::telemetry;
+
+pub fn init_logger() {
+    dotenv().ok();
+
+    // $log_level accepts EnvFilter directives, e.g. "info" or "rest_api=debug,warp=info"
+    let filter = EnvFilter::try_from_env("log_level")
+        .unwrap_or_else(|_| EnvFilter::new("info"));
+    let log_format = env::var("log_format")
+        .unwrap_or_else(|_| String::from("text"));
+
+    // JSON lines for Loki/ELK ingestion, free-form text otherwise. The span
+    // list carries the request span's request_id into events of nested
+    // handler spans.
+    let fmt_layer = if log_format == "json" {
+        tracing_subscriber::fmt::layer()
+            .json()
+            .flatten_event(true)
+            .with_current_span(true)
+            .with_span_list(true)
+            .boxed()
+    } else {
+        tracing_subscriber::fmt::layer().boxed()
+    };
+
+    tracing_subscriber::registry()
+        .with(fmt_layer)
+        .with(telemetry::otlp_layer())
+        .with(filter)
+        .init();
+}
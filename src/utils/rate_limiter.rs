@@ -0,0 +1,125 @@
+use std::collections::HashMap;
+use std::env;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy)]
+pub struct Policy {
+    pub limit: u32,
+    pub period: Duration,
+}
+
+impl Policy {
+    // Reads $<prefix>_requests per $<prefix>_period_secs
+    pub fn from_env(prefix: &str, limit: u32, period_secs: u64) -> Policy {
+        let limit = env::var(format!("{}_requests", prefix))
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(limit);
+        let period_secs = env::var(format!("{}_period_secs", prefix))
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(period_secs);
+
+        Policy {
+            limit: limit.max(1),
+            period: Duration::from_secs(period_secs.max(1)),
+        }
+    }
+
+    fn refill_per_sec(&self) -> f64 {
+        self.limit as f64 / self.period.as_secs_f64()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Decision {
+    pub allowed: bool,
+    pub limit: u32,
+    pub remaining: u32,
+    // Seconds until the bucket is full again
+    pub reset: u64,
+    // Seconds until the next request would be allowed
+    pub retry_after: u64,
+}
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+    // The policy the bucket was filled under, for purging
+    policy: Policy,
+}
+
+impl Bucket {
+    fn tokens_at(&self, now: Instant, policy: &Policy) -> f64 {
+        let elapsed = now.duration_since(self.updated).as_secs_f64();
+        (self.tokens + elapsed * policy.refill_per_sec()).min(policy.limit as f64)
+    }
+}
+
+fn decision(allowed: bool, tokens: f64, policy: &Policy) -> Decision {
+    let capacity = policy.limit as f64;
+    let rate = policy.refill_per_sec();
+
+    Decision {
+        allowed,
+        limit: policy.limit,
+        remaining: tokens.floor() as u32,
+        reset: ((capacity - tokens) / rate).ceil() as u64,
+        retry_after: if allowed { 0 } else { ((1.0 - tokens) / rate).ceil() as u64 },
+    }
+}
+
+// In-memory token buckets, one per key
+pub struct RateLimiter {
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+impl RateLimiter {
+    pub fn new() -> RateLimiter {
+        RateLimiter {
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn check(&self, key: &str, policy: &Policy) -> Decision {
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap();
+
+        let bucket = buckets.entry(key.to_string()).or_insert(Bucket {
+            tokens: policy.limit as f64,
+            updated: now,
+            policy: *policy,
+        });
+        bucket.tokens = bucket.tokens_at(now, policy);
+        bucket.updated = now;
+        bucket.policy = *policy;
+
+        let allowed = bucket.tokens >= 1.0;
+        if allowed {
+            bucket.tokens -= 1.0;
+        }
+
+        decision(allowed, bucket.tokens, policy)
+    }
+
+    // Current state of the key's bucket, without taking a token
+    pub fn peek(&self, key: &str, policy: &Policy) -> Decision {
+        let tokens = match self.buckets.lock().unwrap().get(key) {
+            Some(bucket) => bucket.tokens_at(Instant::now(), policy),
+            None => policy.limit as f64,
+        };
+
+        decision(tokens >= 1.0, tokens, policy)
+    }
+
+    // Drops buckets that have refilled completely under their own policy,
+    // they are indistinguishable from a new one
+    pub fn purge(&self) -> usize {
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap();
+        let before = buckets.len();
+        buckets.retain(|_, bucket| bucket.tokens_at(now, &bucket.policy) < bucket.policy.limit as f64);
+        before - buckets.len()
+    }
+}
@@ -0,0 +1,34 @@
+use std::env;
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::{runtime, trace, Resource};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+// OTLP span export, enabled only when $otlp_endpoint is set (e.g. http://tempo:4317)
+pub fn otlp_layer<S>() -> Option<Box<dyn Layer<S> + Send + Sync>>
+where
+    S: tracing::Subscriber + for<'span> LookupSpan<'span> + Send + Sync,
+{
+    let endpoint = env::var("otlp_endpoint").ok()?;
+    let service_name = env::var("otlp_service_name")
+        .unwrap_or_else(|_| String::from(env!("CARGO_PKG_NAME")));
+
+    let tracer = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(
+            opentelemetry_otlp::new_exporter()
+                .tonic()
+                .with_endpoint(endpoint)
+        )
+        .with_trace_config(
+            trace::config().with_resource(Resource::new(vec![
+                KeyValue::new("service.name", service_name),
+                KeyValue::new("service.version", env!("CARGO_PKG_VERSION")),
+            ]))
+        )
+        .install_batch(runtime::Tokio)
+        .expect("Failed to install OTLP tracer");
+
+    Some(tracing_opentelemetry::layer().with_tracer(tracer).boxed())
+}